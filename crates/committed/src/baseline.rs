@@ -0,0 +1,70 @@
+use std::collections::BTreeSet;
+
+/// Findings to grandfather in, recorded with `--write-baseline`
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Baseline {
+    findings: BTreeSet<Finding>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[serde(deny_unknown_fields)]
+struct Finding {
+    commit: String,
+    rule: String,
+}
+
+impl Baseline {
+    pub(crate) fn load(path: &std::path::Path) -> Result<Self, anyhow::Error> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|err| anyhow::format_err!("could not read {}: {err}", path.display()))?;
+        serde_json::from_str(&text)
+            .map_err(|err| anyhow::format_err!("invalid baseline {}: {err}", path.display()))
+    }
+
+    pub(crate) fn save(&self, path: &std::path::Path) -> Result<(), anyhow::Error> {
+        let mut text = serde_json::to_string_pretty(self)?;
+        text.push('\n');
+        std::fs::write(path, text)
+            .map_err(|err| anyhow::format_err!("could not write {}: {err}", path.display()))
+    }
+
+    pub(crate) fn insert(&mut self, commit: git2::Oid, rule: &str) {
+        self.findings.insert(Finding {
+            commit: commit.to_string(),
+            rule: rule.to_owned(),
+        });
+    }
+
+    pub(crate) fn contains(&self, commit: git2::Oid, rule: &str) -> bool {
+        self.findings.contains(&Finding {
+            commit: commit.to_string(),
+            rule: rule.to_owned(),
+        })
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.findings.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let commit = git2::Oid::from_str("0123456789abcdef0123456789abcdef01234567").unwrap();
+        let mut baseline = Baseline::default();
+        baseline.insert(commit, "subject-length");
+        baseline.insert(commit, "subject-length");
+
+        let text = serde_json::to_string(&baseline).unwrap();
+        let baseline: Baseline = serde_json::from_str(&text).unwrap();
+        assert_eq!(baseline.len(), 1);
+        assert!(baseline.contains(commit, "subject-length"));
+        assert!(!baseline.contains(commit, "imperative"));
+    }
+}
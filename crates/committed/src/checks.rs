@@ -2,10 +2,338 @@ use crate::report;
 use committed::Style;
 
 pub(crate) fn check_message(
+    source: report::Source<'_>,
+    message: &str,
+    config: &crate::config::Config,
+    vocabulary: &Vocabularies,
+    report: report::Report<'_>,
+) -> Result<bool, anyhow::Error> {
+    with_suppressions(source, message, config, report, |report| {
+        check_message_rules(source, message, config, &vocabulary.base, report)
+    })
+}
+
+pub(crate) fn check_commit(
+    source: report::Source<'_>,
+    commit: &git2::Commit<'_>,
+    config: &crate::config::Config,
+    vocabulary: &Vocabularies,
+    allowed_author_re: Option<&regex::Regex>,
+    report: report::Report<'_>,
+) -> Result<bool, anyhow::Error> {
+    let message = commit.message()?;
+    let merge_config;
+    let (config, vocabulary) = match (config.merge_rules(), vocabulary.merge.as_ref()) {
+        (Some(merge_rules), Some(merge_vocabulary)) if 1 < commit.parent_count() => {
+            let mut merged = config.clone();
+            merged.update(merge_rules.clone());
+            merge_config = merged;
+            (&merge_config, merge_vocabulary)
+        }
+        _ => (config, &vocabulary.base),
+    };
+    with_suppressions(source, message, config, report, |report| {
+        let mut failed = false;
+        if let Some(re) = allowed_author_re {
+            failed |= check_allowed_author(source, &commit.author().to_string(), re, report)?;
+        }
+        failed |= check_message_rules(source, message, config, vocabulary, report)?;
+        if !config.merge_commit() {
+            failed |= check_merge_commit(source, commit, report)?;
+        }
+        Ok(failed)
+    })
+}
+
+pub(crate) fn check_patch(
+    source: report::Source<'_>,
+    patch: &crate::patch::Patch,
+    config: &crate::config::Config,
+    vocabulary: &Vocabularies,
+    allowed_author_re: Option<&regex::Regex>,
+    report: report::Report<'_>,
+) -> Result<bool, anyhow::Error> {
+    with_suppressions(source, &patch.message, config, report, |report| {
+        let mut failed = false;
+        if let Some(re) = allowed_author_re {
+            failed |= check_allowed_author(source, &patch.author, re, report)?;
+        }
+        failed |= check_message_rules(source, &patch.message, config, &vocabulary.base, report)?;
+        Ok(failed)
+    })
+}
+
+pub(crate) fn check_tag(
+    source: report::Source<'_>,
+    tag: &git2::Tag<'_>,
+    config: &crate::config::Config,
+    vocabulary: &Vocabularies,
+    report: report::Report<'_>,
+) -> Result<bool, anyhow::Error> {
+    let message = strip_signature(tag.message()?.unwrap_or_default());
+    let tag_config;
+    let (config, vocabulary) = match (config.tag_rules(), vocabulary.tag.as_ref()) {
+        (Some(tag_rules), Some(tag_vocabulary)) => {
+            let mut merged = config.clone();
+            merged.update(tag_rules.clone());
+            tag_config = merged;
+            (&tag_config, tag_vocabulary)
+        }
+        _ => (config, &vocabulary.base),
+    };
+    with_suppressions(source, message, config, report, |report| {
+        let mut failed = check_message_rules(source, message, config, vocabulary, report)?;
+        if config.tag_subject_version() {
+            let subject = message.lines().next().unwrap_or_default();
+            failed |= check_tag_version(source, subject, tag.name()?, report)?;
+        }
+        Ok(failed)
+    })
+}
+
+/// Drop the signature of a signed tag from its message
+fn strip_signature(message: &str) -> &str {
+    [
+        "-----BEGIN PGP SIGNATURE-----",
+        "-----BEGIN SSH SIGNATURE-----",
+    ]
+    .into_iter()
+    .find_map(|marker| message.find(marker))
+    .map(|index| &message[..index])
+    .unwrap_or(message)
+}
+
+pub(crate) fn check_tag_version(
+    source: report::Source<'_>,
+    subject: &str,
+    tag_name: &str,
+    report: report::Report<'_>,
+) -> Result<bool, anyhow::Error> {
+    let version = tag_name.trim_start_matches(|c: char| !c.is_ascii_digit());
+    let version = if version.is_empty() {
+        tag_name
+    } else {
+        version
+    };
+    if subject.contains(version) {
+        Ok(false)
+    } else {
+        report(report::Message::error(
+            source,
+            report::MissingTagVersion {
+                version: version.to_owned(),
+            },
+        ));
+        Ok(true)
+    }
+}
+
+#[test]
+fn tag_version() {
+    let source = report::Source::Tag("v1.2.0");
+    assert!(!check_tag_version(source, "Release 1.2.0", "v1.2.0", &report::print_silent).unwrap());
+    assert!(
+        !check_tag_version(
+            source,
+            "Release parser-1.2.0",
+            "parser-v1.2.0",
+            &report::print_silent
+        )
+        .unwrap()
+    );
+    assert!(check_tag_version(source, "Release 1.1.0", "v1.2.0", &report::print_silent).unwrap());
+    assert!(!check_tag_version(source, "Release stable", "stable", &report::print_silent).unwrap());
+}
+
+pub(crate) fn check_branch_name(
+    source: report::Source<'_>,
+    name: &str,
+    config: &crate::config::Config,
+    branch_name_re: Option<&regex::Regex>,
+    report: report::Report<'_>,
+) -> Result<bool, anyhow::Error> {
+    let mut failed = false;
+    if let Some(re) = branch_name_re
+        && !re.is_match(name)
+    {
+        report(report::Message::error(
+            source,
+            report::InvalidBranchName {
+                name,
+                expected: format!("a match for `{re}`"),
+            },
+        ));
+        failed = true;
+    }
+    if config.branch_type_prefix() {
+        let allowed_types: Vec<_> = config.allowed_types().collect();
+        let used_type = name.split_once('/').map(|(prefix, _)| prefix);
+        if !used_type.is_some_and(|used_type| allowed_types.contains(&used_type)) {
+            report(report::Message::error(
+                source,
+                report::InvalidBranchName {
+                    name,
+                    expected: format!("a prefix of one of {allowed_types:?}, like `feat/`"),
+                },
+            ));
+            failed = true;
+        }
+    }
+    Ok(failed)
+}
+
+#[test]
+fn branch_name() {
+    let config = crate::config::Config {
+        branch_type_prefix: Some(true),
+        ..Default::default()
+    };
+    let re = regex::Regex::new("^[a-z]+/[A-Z]+-[0-9]+-").unwrap();
+    let check = |name| {
+        check_branch_name(
+            report::Source::Branch(name),
+            name,
+            &config,
+            Some(&re),
+            &report::print_silent,
+        )
+        .unwrap()
+    };
+    assert!(!check("feat/PROJ-123-short-desc"));
+    assert!(check("feature/PROJ-123-short-desc"));
+    assert!(check("feat/short-desc"));
+    assert!(check("main"));
+}
+
+/// Rules a commit can't exempt itself from, only `ignore_commits` can
+const UNSUPPRESSIBLE: [&str; 2] = ["allowed-author", "merge-commit"];
+
+/// Run `checks`, dropping findings for rules suppressed by the message's trailers
+///
+/// Suppressions that don't match any finding are reported as warnings.
+fn with_suppressions(
+    source: report::Source<'_>,
+    message: &str,
+    config: &crate::config::Config,
+    report: report::Report<'_>,
+    checks: impl FnOnce(report::Report<'_>) -> Result<bool, anyhow::Error>,
+) -> Result<bool, anyhow::Error> {
+    let suppressed = suppressions(message, config.suppression_trailer());
+    if suppressed.is_empty() {
+        return checks(report);
+    }
+    if !config.allow_suppressions() {
+        log::warn!(
+            "{source}: ignoring `{}` as suppressions are not allowed",
+            config.suppression_trailer()
+        );
+        return checks(report);
+    }
+    let suppressed: Vec<_> = suppressed
+        .into_iter()
+        .filter(|rule| {
+            let policy = UNSUPPRESSIBLE.contains(rule);
+            if policy {
+                log::warn!("{source}: ignoring suppression of `{rule}`, which is repo policy");
+            }
+            !policy
+        })
+        .collect();
+
+    let failed = std::cell::Cell::new(false);
+    let used = std::cell::RefCell::new(Vec::new());
+    let filter = |msg: report::Message<'_>| {
+        let rule = msg.content.rule();
+        if suppressed.contains(&rule) {
+            log::debug!("{source}: suppressed `{rule}`");
+            used.borrow_mut().push(rule);
+        } else {
+            if matches!(msg.severity, report::Severity::Error) {
+                failed.set(true);
+            }
+            report(msg);
+        }
+    };
+    checks(&filter)?;
+
+    let used = used.into_inner();
+    for rule in suppressed {
+        if used.contains(&rule) {
+            continue;
+        }
+        if report::RULES.contains(&rule) {
+            report(report::Message::warning(
+                source,
+                report::UnusedSuppression {
+                    rule: rule.to_owned(),
+                },
+            ));
+        } else {
+            report(report::Message::warning(
+                source,
+                report::UnknownSuppression {
+                    rule: rule.to_owned(),
+                    suggestion: crate::layers::closest(rule, report::RULES),
+                },
+            ));
+        }
+    }
+    Ok(failed.into_inner())
+}
+
+/// Rules listed in `trailer` entries of the message's trailer block
+fn suppressions<'m>(message: &'m str, trailer: &str) -> Vec<&'m str> {
+    let Some(trailers) = message.trim_end().rsplit("\n\n").next() else {
+        return Vec::new();
+    };
+    let mut rules = Vec::new();
+    for line in trailers.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        if unicase::UniCase::new(key.trim()) != unicase::UniCase::new(trailer) {
+            continue;
+        }
+        rules.extend(
+            value
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|rule| !rule.is_empty()),
+        );
+    }
+    rules
+}
+
+#[test]
+fn suppressions_from_trailer() {
+    let message = "Release 1.0.0
+
+Generated by the release tooling
+
+Signed-off-by: Bot <bot@example.com>
+committed-ignore: subject-length, imperative
+Committed-Ignore: wip";
+    assert_eq!(
+        suppressions(message, "Committed-Ignore"),
+        ["subject-length", "imperative", "wip"]
+    );
+}
+
+#[test]
+fn suppressions_only_in_trailer_block() {
+    let message = "Release 1.0.0
+
+Committed-Ignore: subject-length
+
+Signed-off-by: Bot <bot@example.com>";
+    assert!(suppressions(message, "Committed-Ignore").is_empty());
+}
+
+fn check_message_rules(
     source: report::Source<'_>,
     mut message: &str,
     config: &crate::config::Config,
-    report: report::Report,
+    vocabulary: &Vocabulary,
+    report: report::Report<'_>,
 ) -> Result<bool, anyhow::Error> {
     let mut failed = false;
 
@@ -65,7 +393,7 @@ pub(crate) fn check_message(
     };
     if let Some(parsed) = parsed {
         if config.imperative_subject() {
-            failed |= check_imperative_subject(source, parsed.subject(), report)?;
+            failed |= check_imperative_subject(source, parsed.subject(), vocabulary, report)?;
         }
         if config.subject_capitalized() {
             failed |= check_capitalized_subject(source, parsed.subject(), report)?;
@@ -105,7 +433,7 @@ pub(crate) fn check_message(
 fn check_has_message(
     source: report::Source<'_>,
     message: &str,
-    report: report::Report,
+    report: report::Report<'_>,
 ) -> Result<bool, anyhow::Error> {
     if message.trim().is_empty() {
         report(report::Message::error(source, report::EmptyCommit {}));
@@ -119,7 +447,7 @@ pub(crate) fn check_subject_length(
     source: report::Source<'_>,
     message: &str,
     max_length: usize,
-    report: report::Report,
+    report: report::Report<'_>,
 ) -> Result<bool, anyhow::Error> {
     let line = message
         .split('\n')
@@ -148,7 +476,7 @@ pub(crate) fn check_line_length(
     source: report::Source<'_>,
     message: &str,
     max_length: usize,
-    report: report::Report,
+    report: report::Report<'_>,
 ) -> Result<bool, anyhow::Error> {
     let mut failed = false;
     for line in message.split('\n') {
@@ -175,7 +503,7 @@ pub(crate) fn check_hard_line_length(
     source: report::Source<'_>,
     message: &str,
     max_length: usize,
-    report: report::Report,
+    report: report::Report<'_>,
 ) -> Result<bool, anyhow::Error> {
     let mut failed = false;
     for line in message.split('\n') {
@@ -198,7 +526,7 @@ pub(crate) fn check_hard_line_length(
 pub(crate) fn check_capitalized_subject(
     source: report::Source<'_>,
     subject: &str,
-    report: report::Report,
+    report: report::Report<'_>,
 ) -> Result<bool, anyhow::Error> {
     let first_word = subject
         .split_whitespace()
@@ -241,7 +569,7 @@ fn caseless_is_capitalized() {
 pub(crate) fn check_subject_not_punctuated(
     source: report::Source<'_>,
     subject: &str,
-    report: report::Report,
+    report: report::Report<'_>,
 ) -> Result<bool, anyhow::Error> {
     let last = subject
         .chars()
@@ -258,19 +586,109 @@ pub(crate) fn check_subject_not_punctuated(
     }
 }
 
-pub(crate) fn check_imperative_subject(
-    source: report::Source<'_>,
-    subject: &str,
-    report: report::Report,
+/// Project-specific adjustments to the imperative mood check
+#[derive(Debug, Default)]
+pub(crate) struct Vocabulary {
+    /// Words that are always accepted as imperative
+    pub(crate) allow: Vec<String>,
+    /// Words that are always rejected, even if recognized as imperative
+    pub(crate) deny: Vec<String>,
+    /// Leading text to skip before looking for the verb, like ticket IDs or `[component]` tags
+    pub(crate) prefix_re: Option<regex::Regex>,
+}
+
+impl Vocabulary {
+    pub(crate) fn new(config: &crate::config::Config) -> Result<Self, regex::Error> {
+        Ok(Self {
+            allow: config.imperative_allow().map(|s| s.to_owned()).collect(),
+            deny: config.imperative_deny().map(|s| s.to_owned()).collect(),
+            prefix_re: config
+                .imperative_prefix_re()
+                .map(regex::Regex::new)
+                .transpose()?,
+        })
+    }
+
+    fn is_imperative(&self, word: &str) -> bool {
+        let word = unicase::UniCase::new(word);
+        if self
+            .deny
+            .iter()
+            .any(|d| unicase::UniCase::new(d.as_str()) == word)
+        {
+            return false;
+        }
+        if self
+            .allow
+            .iter()
+            .any(|a| unicase::UniCase::new(a.as_str()) == word)
+        {
+            return true;
+        }
+        imperative::Mood::new()
+            .is_imperative(word.as_ref())
+            .unwrap_or(true)
+    }
+
+    fn strip_prefix<'s>(&self, mut subject: &'s str) -> &'s str {
+        let Some(prefix_re) = self.prefix_re.as_ref() else {
+            return subject;
+        };
+        while let Some(matched) = prefix_re.find(subject) {
+            if matched.start() != 0 || matched.is_empty() {
+                break;
+            }
+            subject = subject[matched.end()..].trim_start();
+        }
+        subject
+    }
+}
+
+/// The [`Vocabulary`] of a config and of the nested configs that can take its place
+///
+/// Built once per config rather than per message, as compiling `imperative_prefix_re` is costly.
+#[derive(Debug)]
+pub(crate) struct Vocabularies {
+    base: Vocabulary,
+    /// For merge commits, with `merge_rules`
+    merge: Option<Vocabulary>,
+    /// For annotated tags, with `tag_rules`
+    tag: Option<Vocabulary>,
+}
+
+impl Vocabularies {
+    pub(crate) fn new(config: &crate::config::Config) -> Result<Self, regex::Error> {
+        let nested = |rules: Option<&crate::config::Config>| {
+            rules
+                .map(|rules| {
+                    let mut merged = config.clone();
+                    merged.update(rules.clone());
+                    Vocabulary::new(&merged)
+                })
+                .transpose()
+        };
+        Ok(Self {
+            base: Vocabulary::new(config)?,
+            merge: nested(config.merge_rules())?,
+            tag: nested(config.tag_rules())?,
+        })
+    }
+}
+
+pub(crate) fn check_imperative_subject<'s>(
+    source: report::Source<'s>,
+    subject: &'s str,
+    vocabulary: &Vocabulary,
+    report: report::Report<'_>,
 ) -> Result<bool, anyhow::Error> {
-    let first_word = subject
-        .split_whitespace()
-        .next()
-        .ok_or_else(|| anyhow::anyhow!("Subject cannot be empty"))?;
-    if !imperative::Mood::new()
-        .is_imperative(first_word)
-        .unwrap_or(true)
-    {
+    if subject.trim().is_empty() {
+        anyhow::bail!("Subject cannot be empty");
+    }
+    // Nothing is left to be imperative when the subject is only a prefix, like `[parser]`
+    let Some(first_word) = vocabulary.strip_prefix(subject).split_whitespace().next() else {
+        return Ok(false);
+    };
+    if !vocabulary.is_imperative(first_word) {
         report(report::Message::error(
             source,
             report::Imperative { first_word },
@@ -281,11 +699,71 @@ pub(crate) fn check_imperative_subject(
     }
 }
 
+#[test]
+fn vocabulary_allow() {
+    let vocabulary = Vocabulary {
+        allow: vec!["dedupe".to_owned()],
+        ..Default::default()
+    };
+    assert!(vocabulary.is_imperative("Dedupe"));
+}
+
+#[test]
+fn vocabulary_deny() {
+    let vocabulary = Vocabulary {
+        deny: vec!["add".to_owned()],
+        ..Default::default()
+    };
+    assert!(!vocabulary.is_imperative("add"));
+}
+
+#[test]
+fn vocabulary_strip_prefix() {
+    let vocabulary = Vocabulary {
+        prefix_re: Some(regex::Regex::new(r"^(\[[^\]]*\]|[A-Z]+-[0-9]+:?)").unwrap()),
+        ..Default::default()
+    };
+    assert_eq!(
+        vocabulary.strip_prefix("[parser] PROJ-123: Add greetings"),
+        "Add greetings"
+    );
+    assert_eq!(vocabulary.strip_prefix("Add greetings"), "Add greetings");
+}
+
+#[test]
+fn imperative_only_prefix() {
+    let vocabulary = Vocabulary {
+        prefix_re: Some(regex::Regex::new(r"^\[[^\]]*\]").unwrap()),
+        ..Default::default()
+    };
+    let source = report::Source::Tag("v1.0.0");
+    assert!(
+        !check_imperative_subject(source, "[parser]", &vocabulary, &report::print_silent).unwrap()
+    );
+    assert!(check_imperative_subject(source, "", &vocabulary, &report::print_silent).is_err());
+}
+
+#[test]
+fn vocabularies_nested() {
+    let config = crate::config::Config {
+        imperative_deny: Some(vec!["merge".to_owned()]),
+        merge_rules: Some(Box::new(crate::config::Config {
+            imperative_deny: Some(vec![]),
+            ..Default::default()
+        })),
+        ..Default::default()
+    };
+    let vocabulary = Vocabularies::new(&config).unwrap();
+    assert!(!vocabulary.base.is_imperative("Merge"));
+    assert!(vocabulary.merge.unwrap().is_imperative("Merge"));
+    assert!(vocabulary.tag.is_none());
+}
+
 fn check_allowed_types(
     source: report::Source<'_>,
     parsed: unicase::UniCase<&str>,
     allowed_types: Vec<&str>,
-    report: report::Report,
+    report: report::Report<'_>,
 ) -> Result<bool, anyhow::Error> {
     for allowed_type in allowed_types.iter() {
         let allowed_type = unicase::UniCase::new(allowed_type);
@@ -308,7 +786,7 @@ fn check_allowed_scopes(
     source: report::Source<'_>,
     parsed: unicase::UniCase<&str>,
     allowed_scopes: Vec<&str>,
-    report: report::Report,
+    report: report::Report<'_>,
 ) -> Result<bool, anyhow::Error> {
     for allowed_scope in allowed_scopes.iter() {
         let allowed_scope = unicase::UniCase::new(allowed_scope);
@@ -335,7 +813,7 @@ static WIP_RE: std::sync::LazyLock<regex::Regex> = std::sync::LazyLock::new(|| {
 pub(crate) fn check_wip(
     source: report::Source<'_>,
     message: &str,
-    report: report::Report,
+    report: report::Report<'_>,
 ) -> Result<bool, anyhow::Error> {
     if WIP_RE.is_match(message) {
         report(report::Message::error(source, report::Wip {}));
@@ -359,7 +837,7 @@ const FIXUP_PREFIXES: [&str; 3] = ["fixup! ", "squash! ", "amend! "];
 pub(crate) fn check_fixup(
     source: report::Source<'_>,
     message: &str,
-    report: report::Report,
+    report: report::Report<'_>,
 ) -> Result<bool, anyhow::Error> {
     if FIXUP_PREFIXES
         .iter()
@@ -384,7 +862,7 @@ pub(crate) fn strip_fixup(message: &str) -> &str {
 pub(crate) fn check_merge_commit(
     source: report::Source<'_>,
     commit: &git2::Commit<'_>,
-    report: report::Report,
+    report: report::Report<'_>,
 ) -> Result<bool, anyhow::Error> {
     if 1 < commit.parent_count() {
         report(report::Message::error(
@@ -399,16 +877,15 @@ pub(crate) fn check_merge_commit(
 
 pub(crate) fn check_allowed_author(
     source: report::Source<'_>,
-    commit: &git2::Commit<'_>,
+    author: &str,
     re: &regex::Regex,
-    report: report::Report,
+    report: report::Report<'_>,
 ) -> Result<bool, anyhow::Error> {
-    let author = commit.author().to_string();
-    if !re.is_match(&author) {
+    if !re.is_match(author) {
         report(report::Message::error(
             source,
             report::DisallowedAuthor {
-                used: author,
+                used: author.to_owned(),
                 allowed: re.as_str(),
             },
         ));
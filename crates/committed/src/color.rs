@@ -2,6 +2,7 @@
 pub(crate) struct Palette {
     pub(crate) source: anstyle::Style,
     pub(crate) error: anstyle::Style,
+    pub(crate) warning: anstyle::Style,
     pub(crate) content: anstyle::Style,
 }
 
@@ -10,6 +11,7 @@ impl Palette {
         Self {
             source: anstyle::AnsiColor::Blue.on_default() | anstyle::Effects::BOLD,
             error: anstyle::AnsiColor::Red.on_default() | anstyle::Effects::BOLD,
+            warning: anstyle::AnsiColor::Yellow.on_default() | anstyle::Effects::BOLD,
             content: anstyle::Style::default(),
         }
     }
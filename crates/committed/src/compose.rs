@@ -0,0 +1,321 @@
+use crate::config::Config;
+use crate::config::Style;
+
+/// Answers collected by `committed commit`
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct Answers {
+    pub(crate) type_: Option<String>,
+    pub(crate) scope: Option<String>,
+    pub(crate) subject: String,
+    pub(crate) body: String,
+    pub(crate) breaking: Option<String>,
+    pub(crate) trailers: Vec<String>,
+}
+
+impl Answers {
+    /// The commit message, with long lines of the body wrapped at `line_length`
+    pub(crate) fn message(&self, config: &Config) -> String {
+        let mut paragraphs = vec![format!("{}{}", self.prefix(), self.subject.trim())];
+        let body = self.body.trim();
+        if !body.is_empty() {
+            let body = body
+                .lines()
+                .map(|line| wrap(line, config.line_length()))
+                .collect::<Vec<_>>();
+            paragraphs.push(body.join("\n"));
+        }
+        let mut footer = Vec::new();
+        if let Some(breaking) = self.breaking.as_deref() {
+            footer.push(format!("BREAKING CHANGE: {}", breaking.trim()));
+        }
+        footer.extend(
+            self.trailers
+                .iter()
+                .map(|trailer| trailer.trim().to_owned()),
+        );
+        if !footer.is_empty() {
+            paragraphs.push(footer.join("\n"));
+        }
+        paragraphs.join("\n\n")
+    }
+
+    /// The subject up to the description, like `feat(parser)!: `
+    fn prefix(&self) -> String {
+        let Some(type_) = self.type_.as_deref() else {
+            return String::new();
+        };
+        let scope = self
+            .scope
+            .as_deref()
+            .map(|scope| format!("({scope})"))
+            .unwrap_or_default();
+        let breaking = if self.breaking.is_some() { "!" } else { "" };
+        format!("{type_}{scope}{breaking}: ")
+    }
+}
+
+/// Prompt on `output` for the parts of a commit message, reading answers from `input`
+pub(crate) fn prompt(
+    input: &mut impl std::io::BufRead,
+    output: &mut impl std::io::Write,
+    config: &Config,
+    staged: &[std::path::PathBuf],
+) -> Result<Answers, anyhow::Error> {
+    let mut answers = Answers::default();
+    if config.style() == Style::Conventional {
+        let types = config.allowed_types().collect::<Vec<_>>();
+        answers.type_ = Some(loop {
+            let type_ = ask(input, output, &format!("Type [{}]: ", types.join(", ")))?;
+            if let Some(type_) = types.iter().find(|t| t.eq_ignore_ascii_case(&type_)) {
+                break (*type_).to_owned();
+            }
+            writeln!(output, "Expected one of {}", types.join(", "))?;
+        });
+
+        let scopes = config.allowed_scopes().collect::<Vec<_>>();
+        let inferred = infer_scope(staged, &scopes);
+        let mut question = "Scope".to_owned();
+        if !scopes.is_empty() {
+            question.push_str(&format!(" [{}]", scopes.join(", ")));
+        }
+        if let Some(inferred) = inferred.as_deref() {
+            question.push_str(&format!(" (default `{inferred}`, `-` for none)"));
+        }
+        question.push_str(": ");
+        answers.scope = loop {
+            let scope = ask(input, output, &question)?;
+            let scope = match scope.as_str() {
+                "" => inferred.clone(),
+                "-" => None,
+                _ => Some(scope),
+            };
+            match scope {
+                Some(scope) if !scopes.is_empty() && !scopes.contains(&scope.as_str()) => {
+                    writeln!(output, "Expected one of {}", scopes.join(", "))?;
+                }
+                scope => break scope,
+            }
+        };
+
+        let breaking = ask(
+            input,
+            output,
+            "Breaking change, describing what users need to do (empty for none): ",
+        )?;
+        answers.breaking = (!breaking.is_empty()).then_some(breaking);
+    }
+
+    let prefix = graphemes(&answers.prefix());
+    let max_length = config.subject_length();
+    answers.subject = loop {
+        let question = if max_length == 0 {
+            "Subject: ".to_owned()
+        } else {
+            format!(
+                "Subject ({} characters left): ",
+                max_length.saturating_sub(prefix)
+            )
+        };
+        let subject = ask(input, output, &question)?;
+        if subject.is_empty() {
+            continue;
+        }
+        let length = prefix + graphemes(&subject);
+        if max_length != 0 && max_length < length {
+            writeln!(output, "Subject is {length} of {max_length} characters")?;
+            continue;
+        }
+        break subject;
+    };
+
+    // Unlike for trailers, empty lines are allowed to separate paragraphs
+    writeln!(output, "Body, ending with `.` on its own line:")?;
+    let mut body = Vec::new();
+    loop {
+        let line = ask(input, output, "")?;
+        if line == "." {
+            break;
+        }
+        body.push(line);
+    }
+    answers.body = body.join("\n");
+
+    writeln!(
+        output,
+        "Trailers, like `Fixes: #12`, ending with an empty line:"
+    )?;
+    loop {
+        let trailer = ask(input, output, "")?;
+        if trailer.is_empty() {
+            break;
+        }
+        if is_trailer(&trailer) {
+            answers.trailers.push(trailer);
+        } else {
+            writeln!(output, "Expected `Key: value`")?;
+        }
+    }
+
+    Ok(answers)
+}
+
+fn ask(
+    input: &mut impl std::io::BufRead,
+    output: &mut impl std::io::Write,
+    question: &str,
+) -> Result<String, anyhow::Error> {
+    write!(output, "{question}")?;
+    output.flush()?;
+    let mut answer = String::new();
+    if input.read_line(&mut answer)? == 0 {
+        anyhow::bail!("input ended before the commit message was complete");
+    }
+    Ok(answer.trim().to_owned())
+}
+
+fn is_trailer(line: &str) -> bool {
+    static TRAILER_RE: std::sync::LazyLock<regex::Regex> =
+        std::sync::LazyLock::new(|| regex::Regex::new(r"^[A-Za-z0-9-]+: \S").unwrap());
+    TRAILER_RE.is_match(line)
+}
+
+/// Scope for the staged paths, either an allowed scope they all share or their common directory
+pub(crate) fn infer_scope(paths: &[std::path::PathBuf], allowed: &[&str]) -> Option<String> {
+    let first = paths.first()?;
+    if !allowed.is_empty() {
+        return allowed
+            .iter()
+            .find(|scope| {
+                paths.iter().all(|path| {
+                    path.components()
+                        .any(|component| component.as_os_str() == **scope)
+                })
+            })
+            .map(|scope| (*scope).to_owned());
+    }
+    let mut common = first.parent()?.to_owned();
+    for path in &paths[1..] {
+        while !path.starts_with(&common) {
+            common = common.parent()?.to_owned();
+        }
+    }
+    common
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+}
+
+/// Wrap words onto lines of at most `width` characters, when possible
+fn wrap(text: &str, width: usize) -> String {
+    if width == 0 {
+        return text.to_owned();
+    }
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && width < graphemes(&line) + 1 + graphemes(word) {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    lines.push(line);
+    lines.join("\n")
+}
+
+fn graphemes(text: &str) -> usize {
+    unicode_segmentation::UnicodeSegmentation::graphemes(text, true).count()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn conventional_message() {
+        let config = Config {
+            line_length: Some(20),
+            ..Default::default()
+        };
+        let answers = Answers {
+            type_: Some("feat".to_owned()),
+            scope: Some("parser".to_owned()),
+            subject: "Parse greetings".to_owned(),
+            body: "The parser now understands hello and goodbye".to_owned(),
+            breaking: Some("`parse` returns a `Greeting`".to_owned()),
+            trailers: vec!["Fixes: #12".to_owned()],
+        };
+        assert_eq!(
+            answers.message(&config),
+            "feat(parser)!: Parse greetings
+
+The parser now
+understands hello
+and goodbye
+
+BREAKING CHANGE: `parse` returns a `Greeting`
+Fixes: #12"
+        );
+    }
+
+    #[test]
+    fn prompts() {
+        let config = Config {
+            style: Some(Style::Conventional),
+            subject_length: Some(30),
+            ..Default::default()
+        };
+        let mut input = "bogus\nfeat\n\n\nParse all of the greetings\nParse greetings\nSays hello\n\n- in English\n- in French\n.\nnot a trailer\nFixes: #12\n\n".as_bytes();
+        let mut output = Vec::new();
+        let staged = [
+            std::path::PathBuf::from("crates/parser/src/lib.rs"),
+            std::path::PathBuf::from("crates/parser/tests/greet.rs"),
+        ];
+        let answers = prompt(&mut input, &mut output, &config, &staged).unwrap();
+        assert_eq!(
+            answers,
+            Answers {
+                type_: Some("feat".to_owned()),
+                scope: Some("parser".to_owned()),
+                subject: "Parse greetings".to_owned(),
+                body: "Says hello\n\n- in English\n- in French".to_owned(),
+                breaking: None,
+                trailers: vec!["Fixes: #12".to_owned()],
+            }
+        );
+        assert_eq!(
+            answers.message(&config),
+            "feat(parser): Parse greetings
+
+Says hello
+
+- in English
+- in French
+
+Fixes: #12"
+        );
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Subject (16 characters left): "));
+        assert!(output.contains("Subject is 40 of 30 characters"));
+    }
+
+    #[test]
+    fn scope_from_paths() {
+        let paths = [
+            std::path::PathBuf::from("crates/committed/src/main.rs"),
+            std::path::PathBuf::from("crates/committed/tests/cmd.rs"),
+        ];
+        assert_eq!(infer_scope(&paths, &[]).as_deref(), Some("committed"));
+        assert_eq!(
+            infer_scope(&paths, &["docs", "committed"]).as_deref(),
+            Some("committed")
+        );
+        assert_eq!(infer_scope(&paths, &["docs"]), None);
+        assert_eq!(
+            infer_scope(&[std::path::PathBuf::from("README.md")], &[]),
+            None
+        );
+        assert_eq!(infer_scope(&[], &[]), None);
+    }
+}
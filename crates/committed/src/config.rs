@@ -2,11 +2,12 @@ static DEFAULT_TYPES: &[&str] = &[
     "fix", "feat", "chore", "docs", "style", "refactor", "perf", "test",
 ];
 
+/// Commit style convention
 #[derive(
     Copy, Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, derive_more::Display,
 )]
 #[serde(rename_all = "snake_case")]
-#[cfg_attr(feature = "unstable-schema", derive(schemars::JsonSchema))]
+#[derive(schemars::JsonSchema)]
 pub(crate) enum Style {
     #[serde(alias = "Conventional")]
     Conventional,
@@ -14,38 +15,265 @@ pub(crate) enum Style {
     None,
 }
 
+/// Commit exempt from checks
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+#[derive(schemars::JsonSchema)]
+pub(crate) enum IgnoreCommit {
+    /// Full or abbreviated commit id, exempt from all rules
+    Commit(String),
+    /// Full or abbreviated commit id, exempt from only the listed rules
+    Rules { commit: String, rules: Vec<String> },
+}
+
+impl IgnoreCommit {
+    pub(crate) fn commit(&self) -> &str {
+        match self {
+            Self::Commit(commit) | Self::Rules { commit, .. } => commit,
+        }
+    }
+
+    /// Rules the commit is exempt from, with `None` for all rules
+    pub(crate) fn rules(&self) -> Option<&[String]> {
+        match self {
+            Self::Commit(_) => None,
+            Self::Rules { rules, .. } => Some(rules),
+        }
+    }
+}
+
 #[derive(Clone, Default, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
+#[serde(deny_unknown_fields)]
 #[non_exhaustive]
-#[cfg_attr(feature = "unstable-schema", derive(schemars::JsonSchema))]
+#[derive(schemars::JsonSchema)]
 pub(crate) struct Config {
-    #[cfg_attr(feature = "unstable-schema", schemars(extend("format" = "regex")))]
+    /// JSON schema for editor support, ignored by `committed`
+    #[serde(rename = "$schema")]
+    pub(crate) schema: Option<String>,
+    /// Presets (`preset:<name>`) or config files to build on, in order
+    pub(crate) extends: Option<Vec<String>>,
+    /// Authors to ignore the commits for. Generally used with bots out of your control.
+    #[serde(default, deserialize_with = "deserialize_regex")]
+    #[schemars(extend("format" = "regex"))]
     pub(crate) ignore_author_re: Option<String>,
+    /// Commits to ignore, by full or abbreviated id, optionally for only some rules
+    #[serde(default, deserialize_with = "deserialize_ignore_commits")]
+    pub(crate) ignore_commits: Option<Vec<IgnoreCommit>>,
+    /// Number of columns the subject can occupy
     pub(crate) subject_length: Option<usize>,
+    /// Whether the subject is required to be capitalized
     pub(crate) subject_capitalized: Option<bool>,
+    /// Prevent the subject from ending in punctuation
     pub(crate) subject_not_punctuated: Option<bool>,
+    /// Require the subject to start with an imperative verb
     pub(crate) imperative_subject: Option<bool>,
+    /// Words to always accept as imperative verbs
+    pub(crate) imperative_allow: Option<Vec<String>>,
+    /// Words to always reject as imperative verbs
+    pub(crate) imperative_deny: Option<Vec<String>>,
+    /// Leading text to skip when looking for the imperative verb, like ticket IDs
+    #[serde(default, deserialize_with = "deserialize_regex")]
+    #[schemars(extend("format" = "regex"))]
+    pub(crate) imperative_prefix_re: Option<String>,
+    /// Disallow fixup commits
+    #[schemars(extend("x-cli-flag" = "--no-fixup"))]
     pub(crate) no_fixup: Option<bool>,
+    /// Disallow WIP commits
+    #[schemars(extend("x-cli-flag" = "--no-wip"))]
     pub(crate) no_wip: Option<bool>,
+    /// Max number of columns any line can occupy, with 0 for no limit
     pub(crate) hard_line_length: Option<usize>,
+    /// Number of columns any line with a break can occupy, including subject, with 0 for no limit
     pub(crate) line_length: Option<usize>,
+    /// Commit style convention
     pub(crate) style: Option<Style>,
+    /// (Conventional) Accepted commit types
     pub(crate) allowed_types: Option<Vec<String>>,
+    /// (Conventional) Accepted commit scopes, with an empty list allowing all scopes
     pub(crate) allowed_scopes: Option<Vec<String>>,
+    /// Without commits specified, check those since `HEAD` branched from its upstream, rather than `HEAD`
+    #[schemars(extend("x-cli-flag" = "--since-upstream"))]
+    pub(crate) since_upstream: Option<bool>,
+    /// Ignore `<!-- ... -->` comments, like pull request template instructions, with `--pr-event`
+    pub(crate) strip_pr_comments: Option<bool>,
+    /// Require branch names to match this regular expression
+    #[serde(default, deserialize_with = "deserialize_regex")]
+    #[schemars(extend("format" = "regex"))]
+    pub(crate) branch_name_re: Option<String>,
+    /// Require branch names to start with one of `allowed_types`, like `feat/`
+    pub(crate) branch_type_prefix: Option<bool>,
+    /// Allow merge commits
+    #[schemars(extend("x-cli-flag" = "--no-merge-commit"))]
     pub(crate) merge_commit: Option<bool>,
-    #[cfg_attr(feature = "unstable-schema", schemars(extend("format" = "regex")))]
+    /// Rules to use instead for merge commits, like `style = "none"` for `Merge pull request #...`
+    #[serde(default, deserialize_with = "deserialize_merge_rules")]
+    pub(crate) merge_rules: Option<Box<Config>>,
+    /// Rules to use instead for annotated tag messages
+    #[serde(default, deserialize_with = "deserialize_tag_rules")]
+    pub(crate) tag_rules: Option<Box<Config>>,
+    /// (Tags) Require the subject to mention the tag's version, like `1.2.0` for `v1.2.0`
+    pub(crate) tag_subject_version: Option<bool>,
+    /// Rules to use instead for pushes to refs matching the glob, like `refs/heads/main`, with
+    /// `committed hook pre-receive` or `pre-push`
+    #[serde(default, deserialize_with = "deserialize_ref_rules")]
+    pub(crate) ref_rules: Option<std::collections::BTreeMap<String, Config>>,
+    /// Allow skipping rules for a commit with a trailer like `Committed-Ignore: subject-length`
+    pub(crate) allow_suppressions: Option<bool>,
+    /// Trailer for listing rules to skip for a commit
+    pub(crate) suppression_trailer: Option<String>,
+    /// Require commit author to match this regular expression
+    #[serde(default, deserialize_with = "deserialize_regex")]
+    #[schemars(extend("format" = "regex"))]
     pub(crate) allowed_author_re: Option<String>,
 }
 
+/// Reject invalid regexes while the location in the config file is still known
+fn deserialize_regex<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let re = <Option<String> as serde::Deserialize>::deserialize(deserializer)?;
+    if let Some(re) = re.as_deref() {
+        regex::Regex::new(re).map_err(serde::de::Error::custom)?;
+    }
+    Ok(re)
+}
+
+fn deserialize_ignore_commits<'de, D>(
+    deserializer: D,
+) -> Result<Option<Vec<IgnoreCommit>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let ignore = <Option<Vec<IgnoreCommit>> as serde::Deserialize>::deserialize(deserializer)?;
+    for rule in ignore.iter().flatten().filter_map(|i| i.rules()).flatten() {
+        if !crate::report::RULES.contains(&rule.as_str()) {
+            return Err(serde::de::Error::custom(format_args!(
+                "unknown rule `{rule}`, expected one of {}",
+                crate::report::RULES
+                    .map(|rule| format!("`{rule}`"))
+                    .join(", ")
+            )));
+        }
+    }
+    Ok(ignore)
+}
+
+/// Fields that only apply to the config as a whole, not to the commits nested rules pick out
+const NOT_PER_COMMIT: &[&str] = &[
+    "$schema",
+    "extends",
+    "ignore_author_re",
+    "ignore_commits",
+    "since_upstream",
+    "strip_pr_comments",
+    "branch_name_re",
+    "branch_type_prefix",
+    "ref_rules",
+];
+
+/// Reject fields of nested rules, like `merge_rules`, that would be silently ignored
+fn check_nested<E: serde::de::Error>(
+    rules: &Config,
+    table: &str,
+    ignored: &[&[&str]],
+) -> Result<(), E> {
+    let fields = toml::Table::try_from(rules).map_err(E::custom)?;
+    if let Some(field) = ignored
+        .iter()
+        .copied()
+        .flatten()
+        .find(|field| fields.contains_key(**field))
+    {
+        return Err(E::custom(format_args!(
+            "`{field}` can't be set in `{table}`"
+        )));
+    }
+    Ok(())
+}
+
+fn deserialize_merge_rules<'de, D>(deserializer: D) -> Result<Option<Box<Config>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let rules = <Option<Box<Config>> as serde::Deserialize>::deserialize(deserializer)?;
+    if let Some(rules) = rules.as_deref() {
+        check_nested(
+            rules,
+            "merge_rules",
+            &[
+                NOT_PER_COMMIT,
+                &[
+                    "allowed_author_re",
+                    "merge_rules",
+                    "tag_rules",
+                    "tag_subject_version",
+                ],
+            ],
+        )?;
+    }
+    Ok(rules)
+}
+
+fn deserialize_tag_rules<'de, D>(deserializer: D) -> Result<Option<Box<Config>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let rules = <Option<Box<Config>> as serde::Deserialize>::deserialize(deserializer)?;
+    if let Some(rules) = rules.as_deref() {
+        check_nested(
+            rules,
+            "tag_rules",
+            &[
+                NOT_PER_COMMIT,
+                &[
+                    "allowed_author_re",
+                    "merge_commit",
+                    "merge_rules",
+                    "tag_rules",
+                ],
+            ],
+        )?;
+    }
+    Ok(rules)
+}
+
+fn deserialize_ref_rules<'de, D>(
+    deserializer: D,
+) -> Result<Option<std::collections::BTreeMap<String, Config>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let rules =
+        <Option<std::collections::BTreeMap<String, Config>> as serde::Deserialize>::deserialize(
+            deserializer,
+        )?;
+    for (pattern, rules) in rules.iter().flatten() {
+        check_nested(
+            rules,
+            &format!("ref_rules.\"{pattern}\""),
+            &[NOT_PER_COMMIT],
+        )?;
+    }
+    Ok(rules)
+}
+
 impl Config {
     pub(crate) fn from_defaults() -> Self {
         let empty = Self::default();
         Self {
+            schema: None,
+            extends: None,
             ignore_author_re: empty.ignore_author_re().map(|s| s.to_owned()),
+            ignore_commits: Some(empty.ignore_commits().to_vec()),
             subject_length: Some(empty.subject_length()),
             subject_capitalized: Some(empty.subject_capitalized()),
             subject_not_punctuated: Some(empty.subject_not_punctuated()),
             imperative_subject: Some(empty.imperative_subject()),
+            imperative_allow: Some(empty.imperative_allow().map(|s| s.to_owned()).collect()),
+            imperative_deny: Some(empty.imperative_deny().map(|s| s.to_owned()).collect()),
+            imperative_prefix_re: empty.imperative_prefix_re().map(|s| s.to_owned()),
             no_fixup: Some(empty.no_fixup()),
             no_wip: Some(empty.no_wip()),
             hard_line_length: Some(empty.hard_line_length()),
@@ -53,15 +281,31 @@ impl Config {
             style: Some(empty.style()),
             allowed_types: Some(empty.allowed_types().map(|s| s.to_owned()).collect()),
             allowed_scopes: Some(empty.allowed_scopes().map(|s| s.to_owned()).collect()),
+            since_upstream: Some(empty.since_upstream()),
+            strip_pr_comments: Some(empty.strip_pr_comments()),
+            branch_name_re: empty.branch_name_re().map(|s| s.to_owned()),
+            branch_type_prefix: Some(empty.branch_type_prefix()),
             merge_commit: Some(empty.merge_commit()),
+            merge_rules: None,
+            tag_rules: None,
+            tag_subject_version: Some(empty.tag_subject_version()),
+            ref_rules: None,
+            allow_suppressions: Some(empty.allow_suppressions()),
+            suppression_trailer: Some(empty.suppression_trailer().to_owned()),
             allowed_author_re: empty.allowed_author_re().map(|s| s.to_owned()),
         }
     }
 
     pub(crate) fn update(&mut self, source: Self) {
+        if let Some(source) = source.extends {
+            self.extends = Some(source);
+        }
         if let Some(source) = source.ignore_author_re {
             self.ignore_author_re = Some(source);
         }
+        if let Some(source) = source.ignore_commits {
+            self.ignore_commits = Some(source);
+        }
         if let Some(source) = source.subject_length {
             self.subject_length = Some(source);
         }
@@ -74,6 +318,15 @@ impl Config {
         if let Some(source) = source.imperative_subject {
             self.imperative_subject = Some(source);
         }
+        if let Some(source) = source.imperative_allow {
+            self.imperative_allow = Some(source);
+        }
+        if let Some(source) = source.imperative_deny {
+            self.imperative_deny = Some(source);
+        }
+        if let Some(source) = source.imperative_prefix_re {
+            self.imperative_prefix_re = Some(source);
+        }
         if let Some(source) = source.no_fixup {
             self.no_fixup = Some(source);
         }
@@ -95,9 +348,53 @@ impl Config {
         if let Some(source) = source.allowed_scopes {
             self.allowed_scopes = Some(source);
         }
+        if let Some(source) = source.since_upstream {
+            self.since_upstream = Some(source);
+        }
+        if let Some(source) = source.strip_pr_comments {
+            self.strip_pr_comments = Some(source);
+        }
+        if let Some(source) = source.branch_name_re {
+            self.branch_name_re = Some(source);
+        }
+        if let Some(source) = source.branch_type_prefix {
+            self.branch_type_prefix = Some(source);
+        }
         if let Some(source) = source.merge_commit {
             self.merge_commit = Some(source);
         }
+        if let Some(source) = source.merge_rules {
+            match self.merge_rules.as_mut() {
+                Some(merge_rules) => merge_rules.update(*source),
+                None => self.merge_rules = Some(source),
+            }
+        }
+        if let Some(source) = source.tag_rules {
+            match self.tag_rules.as_mut() {
+                Some(tag_rules) => tag_rules.update(*source),
+                None => self.tag_rules = Some(source),
+            }
+        }
+        if let Some(source) = source.tag_subject_version {
+            self.tag_subject_version = Some(source);
+        }
+        if let Some(source) = source.ref_rules {
+            let ref_rules = self.ref_rules.get_or_insert_with(Default::default);
+            for (pattern, source) in source {
+                match ref_rules.get_mut(&pattern) {
+                    Some(rules) => rules.update(source),
+                    None => {
+                        ref_rules.insert(pattern, source);
+                    }
+                }
+            }
+        }
+        if let Some(source) = source.allow_suppressions {
+            self.allow_suppressions = Some(source);
+        }
+        if let Some(source) = source.suppression_trailer {
+            self.suppression_trailer = Some(source);
+        }
         if let Some(source) = source.allowed_author_re {
             self.allowed_author_re = Some(source);
         }
@@ -107,6 +404,10 @@ impl Config {
         self.ignore_author_re.as_deref()
     }
 
+    pub(crate) fn ignore_commits(&self) -> &[IgnoreCommit] {
+        self.ignore_commits.as_deref().unwrap_or_default()
+    }
+
     pub(crate) fn subject_length(&self) -> usize {
         self.subject_length.unwrap_or(50)
     }
@@ -123,6 +424,30 @@ impl Config {
         self.imperative_subject.unwrap_or(true)
     }
 
+    pub(crate) fn imperative_allow<'s>(&'s self) -> Box<dyn Iterator<Item = &'s str> + 's> {
+        self.imperative_allow
+            .as_ref()
+            .map(|v| {
+                let b: Box<dyn Iterator<Item = &str>> = Box::new(v.iter().map(|s| s.as_str()));
+                b
+            })
+            .unwrap_or_else(|| Box::new([].iter().copied()))
+    }
+
+    pub(crate) fn imperative_deny<'s>(&'s self) -> Box<dyn Iterator<Item = &'s str> + 's> {
+        self.imperative_deny
+            .as_ref()
+            .map(|v| {
+                let b: Box<dyn Iterator<Item = &str>> = Box::new(v.iter().map(|s| s.as_str()));
+                b
+            })
+            .unwrap_or_else(|| Box::new([].iter().copied()))
+    }
+
+    pub(crate) fn imperative_prefix_re(&self) -> Option<&str> {
+        self.imperative_prefix_re.as_deref()
+    }
+
     pub(crate) fn no_fixup(&self) -> bool {
         self.no_fixup.unwrap_or(true)
     }
@@ -163,19 +488,90 @@ impl Config {
             .unwrap_or_else(|| Box::new([].iter().copied()))
     }
 
+    pub(crate) fn since_upstream(&self) -> bool {
+        self.since_upstream.unwrap_or(false)
+    }
+
+    pub(crate) fn strip_pr_comments(&self) -> bool {
+        self.strip_pr_comments.unwrap_or(true)
+    }
+
+    pub(crate) fn branch_name_re(&self) -> Option<&str> {
+        self.branch_name_re.as_deref()
+    }
+
+    pub(crate) fn branch_type_prefix(&self) -> bool {
+        self.branch_type_prefix.unwrap_or(false)
+    }
+
     pub(crate) fn merge_commit(&self) -> bool {
         self.merge_commit.unwrap_or(true)
     }
 
+    pub(crate) fn merge_rules(&self) -> Option<&Config> {
+        self.merge_rules.as_deref()
+    }
+
+    pub(crate) fn tag_rules(&self) -> Option<&Config> {
+        self.tag_rules.as_deref()
+    }
+
+    pub(crate) fn tag_subject_version(&self) -> bool {
+        self.tag_subject_version.unwrap_or(false)
+    }
+
+    /// Rules for each ref glob, in order of the glob
+    pub(crate) fn ref_rules(&self) -> impl Iterator<Item = (&str, &Config)> {
+        self.ref_rules
+            .iter()
+            .flatten()
+            .map(|(pattern, rules)| (pattern.as_str(), rules))
+    }
+
+    pub(crate) fn allow_suppressions(&self) -> bool {
+        self.allow_suppressions.unwrap_or(true)
+    }
+
+    pub(crate) fn suppression_trailer(&self) -> &str {
+        self.suppression_trailer
+            .as_deref()
+            .unwrap_or("Committed-Ignore")
+    }
+
     pub(crate) fn allowed_author_re(&self) -> Option<&str> {
         self.allowed_author_re.as_deref()
     }
 }
 
-#[cfg(feature = "unstable-schema")]
+/// JSON schema for [`Config`], including each field's default
+pub(crate) fn schema() -> schemars::Schema {
+    let mut schema = schemars::schema_for!(Config);
+    let defaults = serde_json::to_value(Config::from_defaults()).expect("config is serializable");
+    if let (Some(defaults), Some(properties)) = (
+        defaults.as_object(),
+        schema.get_mut("properties").and_then(|p| p.as_object_mut()),
+    ) {
+        for (key, default) in defaults {
+            if default.is_null() {
+                continue;
+            }
+            if let Some(property) = properties.get_mut(key).and_then(|p| p.as_object_mut()) {
+                property.insert("default".to_owned(), default.clone());
+            }
+        }
+    }
+    schema
+}
+
+/// [`schema`] as published in `config.schema.json`
+pub(crate) fn schema_json() -> Result<String, anyhow::Error> {
+    let mut output = serde_json::to_string_pretty(&schema())?;
+    output.push('\n');
+    Ok(output)
+}
+
 #[test]
 fn dump_schema() {
-    let schema = schemars::schema_for!(Config);
-    let dump = serde_json::to_string_pretty(&schema).unwrap();
+    let dump = schema_json().unwrap();
     snapbox::assert_data_eq!(dump, snapbox::file!("../../../config.schema.json").raw());
 }
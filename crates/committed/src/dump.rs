@@ -0,0 +1,163 @@
+use std::fmt::Write as _;
+
+use crate::config::Config;
+use crate::layers::Layers;
+
+/// Render the fully defaulted config as TOML, documenting each field
+///
+/// The documentation comes from [`crate::config::schema`].
+pub(crate) fn to_toml(layers: &Layers, show_origin: bool) -> Result<String, anyhow::Error> {
+    let schema = crate::config::schema();
+    let values = defaulted(layers)?;
+
+    let mut output = String::new();
+    for (key, property) in properties(&schema) {
+        if !output.is_empty() {
+            output.push('\n');
+        }
+        if let Some(description) = property.get("description").and_then(|d| d.as_str()) {
+            for line in description.lines() {
+                let _ = writeln!(output, "# {line}");
+            }
+        }
+        if let Some(allowed) = allowed_values(&schema, property) {
+            let _ = writeln!(output, "# Allowed values: {allowed}");
+        }
+        match property.get("default").filter(|d| !d.is_null()) {
+            Some(default) => {
+                let _ = writeln!(output, "# Default: {}", to_inline_toml(default)?);
+            }
+            None => {
+                let _ = writeln!(output, "# Default: (none)");
+            }
+        }
+        if let Some(flag) = property.get("x-cli-flag").and_then(|f| f.as_str()) {
+            let _ = writeln!(output, "# Overridden by: {flag}");
+        }
+        if show_origin {
+            let _ = writeln!(output, "# Source: {}", layers.origin(key)?);
+        }
+        match values.get(key).filter(|v| !v.is_null()) {
+            Some(value) => {
+                let _ = writeln!(output, "{key} = {}", to_inline_toml(value)?);
+            }
+            None => {
+                let _ = writeln!(output, "# {key} = {}", placeholder(property));
+            }
+        }
+    }
+    Ok(output)
+}
+
+/// Render the fully defaulted config as JSON, for tooling
+pub(crate) fn to_json(layers: &Layers, show_origin: bool) -> Result<String, anyhow::Error> {
+    let values = defaulted(layers)?;
+
+    let mut output = serde_json::Map::new();
+    for (key, value) in values {
+        if value.is_null() {
+            continue;
+        }
+        let value = if show_origin {
+            serde_json::json!({
+                "value": value,
+                "origin": layers.origin(&key)?.to_string(),
+            })
+        } else {
+            value
+        };
+        output.insert(key, value);
+    }
+    let mut output = serde_json::to_string_pretty(&output)?;
+    output.push('\n');
+    Ok(output)
+}
+
+fn defaulted(layers: &Layers) -> Result<serde_json::Map<String, serde_json::Value>, anyhow::Error> {
+    let mut defaulted_config = Config::from_defaults();
+    defaulted_config.update(layers.merged());
+    match serde_json::to_value(defaulted_config)? {
+        serde_json::Value::Object(mut values) => {
+            for value in values.values_mut() {
+                strip_nested_nulls(value);
+            }
+            Ok(values)
+        }
+        _ => unreachable!("config is a struct"),
+    }
+}
+
+/// Drop unset fields of nested configs, like `merge_rules`, which TOML can't represent
+fn strip_nested_nulls(value: &mut serde_json::Value) {
+    if let serde_json::Value::Object(fields) = value {
+        fields.retain(|_, field| !field.is_null());
+        for field in fields.values_mut() {
+            strip_nested_nulls(field);
+        }
+    }
+}
+
+fn properties(
+    schema: &schemars::Schema,
+) -> impl Iterator<Item = (&str, &serde_json::Map<String, serde_json::Value>)> {
+    schema
+        .get("properties")
+        .and_then(|p| p.as_object())
+        .into_iter()
+        .flatten()
+        .filter(|(key, _)| !key.starts_with('$'))
+        .filter_map(|(key, property)| Some((key.as_str(), property.as_object()?)))
+}
+
+/// Enumerated values, following `$ref`s into the schema's definitions
+fn allowed_values(
+    schema: &schemars::Schema,
+    property: &serde_json::Map<String, serde_json::Value>,
+) -> Option<String> {
+    let reference = property
+        .get("anyOf")
+        .and_then(|a| a.as_array())
+        .into_iter()
+        .flatten()
+        .find_map(|variant| variant.get("$ref").and_then(|r| r.as_str()))?;
+    let pointer = reference.strip_prefix('#')?;
+    let values = schema.pointer(pointer)?.get("enum")?.as_array()?;
+    let values = values
+        .iter()
+        .map(to_inline_toml)
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+    Some(values.join(", "))
+}
+
+fn placeholder(property: &serde_json::Map<String, serde_json::Value>) -> &'static str {
+    if property.get("format").and_then(|f| f.as_str()) == Some("regex") {
+        return r#""<regex>""#;
+    }
+    let is_config = property
+        .get("anyOf")
+        .and_then(|a| a.as_array())
+        .into_iter()
+        .flatten()
+        .any(|variant| variant.get("$ref").and_then(|r| r.as_str()) == Some("#"));
+    if is_config {
+        return "{}";
+    }
+    let is_table = property
+        .get("type")
+        .and_then(|t| t.as_array())
+        .is_some_and(|t| t.iter().any(|t| t == "object"));
+    if is_table {
+        return "{}";
+    }
+    let is_array = property
+        .get("type")
+        .and_then(|t| t.as_array())
+        .is_some_and(|t| t.iter().any(|t| t == "array"));
+    if is_array { "[]" } else { r#""""# }
+}
+
+fn to_inline_toml(value: &serde_json::Value) -> Result<String, anyhow::Error> {
+    let value = toml::Value::try_from(value)?;
+    Ok(value.to_string())
+}
@@ -1,42 +1,317 @@
+/// Commits selected by `git rev-list` style revisions
 pub(crate) struct RevSpec<'r> {
     repo: &'r git2::Repository,
-    from: git2::Commit<'r>,
-    to: Option<git2::Commit<'r>>,
+    include: Vec<git2::Oid>,
+    exclude: Vec<git2::Oid>,
+    walk: bool,
+    limits: Limits,
+}
+
+/// Restrictions on the commits walked
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Limits {
+    pub(crate) max_count: Option<usize>,
+    pub(crate) since: Option<jiff::Timestamp>,
+    pub(crate) until: Option<jiff::Timestamp>,
+    /// Only follow the first parent of merge commits
+    pub(crate) first_parent: bool,
+    pub(crate) merges: Merges,
+}
+
+/// Which commits to check, based on being a merge commit
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) enum Merges {
+    #[default]
+    Include,
+    Exclude,
+    Only,
+}
+
+impl Limits {
+    /// Whether history is walked, even when only single commits are selected
+    pub(crate) fn walks(&self) -> bool {
+        self.max_count.is_some() || self.since.is_some() || self.until.is_some()
+    }
+
+    fn contains(&self, commit: &git2::Commit<'_>) -> bool {
+        let time = commit.time().seconds();
+        let is_merge = 1 < commit.parent_count();
+        self.since.is_none_or(|since| since.as_second() <= time)
+            && self.until.is_none_or(|until| time <= until.as_second())
+            && match self.merges {
+                Merges::Include => true,
+                Merges::Exclude => !is_merge,
+                Merges::Only => is_merge,
+            }
+    }
 }
 
 impl<'r> RevSpec<'r> {
+    pub(crate) fn new(repo: &'r git2::Repository) -> Self {
+        Self {
+            repo,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            walk: false,
+            limits: Limits::default(),
+        }
+    }
+
     pub(crate) fn parse(repo: &'r git2::Repository, revspec: &str) -> Result<Self, anyhow::Error> {
-        let commits = repo.revparse(revspec)?;
-        let from = commits.from().unwrap().as_commit().unwrap().clone();
-        let to = commits.to().map(|o| o.as_commit().unwrap().clone());
-        let from = if let Some(to) = to.as_ref() {
-            let merged_from_id = repo.merge_base(from.id(), to.id())?;
-            if merged_from_id != from.id() {
-                log::debug!(
-                    "from/to for revspec {revspec} are on different branches, relying on common parent {merged_from_id}"
-                );
+        let mut spec = Self::new(repo);
+        spec.push(revspec)?;
+        Ok(spec)
+    }
+
+    /// Select commits for a revision, like `A`, `^A`, `A..B`, or `A...B`
+    ///
+    /// When only single commits are selected, without any [`Limits`], only those commits are
+    /// checked rather than their history.
+    pub(crate) fn push(&mut self, revspec: &str) -> Result<(), anyhow::Error> {
+        if let Some(excluded) = revspec.strip_prefix('^') {
+            let object = self.repo.revparse_single(excluded)?;
+            self.exclude.push(peel_to_commit(excluded, &object)?.id());
+            self.walk = true;
+            return Ok(());
+        }
+
+        let commits = self.repo.revparse(revspec)?;
+        let from = commits
+            .from()
+            .map(|from| peel_to_commit(revspec, from))
+            .transpose()?;
+        let to = commits
+            .to()
+            .map(|to| peel_to_commit(revspec, to))
+            .transpose()?;
+        match (from, to) {
+            (Some(from), None) => {
+                self.include.push(from.id());
+            }
+            (Some(from), Some(to)) if commits.mode().contains(git2::RevparseMode::MERGE_BASE) => {
+                self.include.extend([from.id(), to.id()]);
+                self.exclude
+                    .extend(self.repo.merge_bases(from.id(), to.id())?.iter().copied());
+                self.walk = true;
             }
-            repo.find_commit(merged_from_id)?
+            (Some(from), Some(to)) => {
+                let merged_from_id = self.repo.merge_base(from.id(), to.id())?;
+                if merged_from_id != from.id() {
+                    log::debug!(
+                        "from/to for revspec {revspec} are on different branches, relying on common parent {merged_from_id}"
+                    );
+                }
+                self.include.push(to.id());
+                self.exclude.push(merged_from_id);
+                self.walk = true;
+            }
+            (None, _) => anyhow::bail!("`{revspec}` does not name a commit"),
+        }
+        Ok(())
+    }
+
+    /// Select all commits reachable from `HEAD` and any ref, like `git rev-list --all`
+    pub(crate) fn push_all(&mut self) -> Result<(), anyhow::Error> {
+        if let Ok(head) = self.repo.head() {
+            self.include.push(head.peel_to_commit()?.id());
+        }
+        for reference in self.repo.references()? {
+            self.push_reference(&reference?);
+        }
+        self.walk = true;
+        Ok(())
+    }
+
+    /// Select all commits reachable from branches matching `glob`, like `git rev-list --branches`
+    pub(crate) fn push_branches(&mut self, glob: &str) -> Result<(), anyhow::Error> {
+        let glob = if glob.contains(['?', '*', '[']) {
+            format!("refs/heads/{glob}")
         } else {
-            from
+            format!("refs/heads/{glob}/*")
         };
-        Ok(Self { repo, from, to })
+        for reference in self.repo.references_glob(&glob)? {
+            self.push_reference(&reference?);
+        }
+        self.walk = true;
+        Ok(())
+    }
+
+    fn push_reference(&mut self, reference: &git2::Reference<'_>) {
+        match reference.peel_to_commit() {
+            Ok(commit) => self.include.push(commit.id()),
+            Err(_) => log::debug!(
+                "Skipping {}, not a commit",
+                String::from_utf8_lossy(reference.name_bytes())
+            ),
+        }
+    }
+
+    /// Select the commit with this id
+    pub(crate) fn push_id(&mut self, id: git2::Oid) -> Result<(), anyhow::Error> {
+        let object = self.repo.find_object(id, None)?;
+        self.include
+            .push(peel_to_commit(&id.to_string(), &object)?.id());
+        Ok(())
+    }
+
+    /// Skip commits reachable from the commit with this id, like `^A`
+    pub(crate) fn hide_id(&mut self, id: git2::Oid) -> Result<(), anyhow::Error> {
+        let object = self.repo.find_object(id, None)?;
+        self.exclude
+            .push(peel_to_commit(&id.to_string(), &object)?.id());
+        self.walk = true;
+        Ok(())
+    }
+
+    /// Skip commits reachable from refs matching `glob`, like `--not --glob=<glob>`
+    pub(crate) fn hide_references(&mut self, glob: &str) -> Result<(), anyhow::Error> {
+        for reference in self.repo.references_glob(glob)? {
+            if let Ok(commit) = reference?.peel_to_commit() {
+                self.exclude.push(commit.id());
+            }
+        }
+        self.walk = true;
+        Ok(())
+    }
+
+    pub(crate) fn limit(&mut self, limits: Limits) {
+        self.limits = limits;
     }
 
-    pub(crate) fn iter(&self) -> impl Iterator<Item = git2::Commit<'r>> {
-        if let Some(to) = self.to.as_ref() {
-            let range = format!("{}..{}", self.from.id(), to.id());
-            let mut revwalk = self.repo.revwalk().unwrap();
-            revwalk.push_range(&range).unwrap();
-            let revwalk = RevWalkIterator {
+    pub(crate) fn iter(
+        &self,
+    ) -> Result<impl Iterator<Item = git2::Commit<'r>> + use<'r, '_>, anyhow::Error> {
+        let commits = if self.walk || self.limits.walks() {
+            let mut revwalk = self.repo.revwalk()?;
+            if self.limits.first_parent {
+                revwalk.simplify_first_parent()?;
+            }
+            for id in &self.include {
+                revwalk.push(*id)?;
+            }
+            for id in &self.exclude {
+                revwalk.hide(*id)?;
+            }
+            itertools::Either::Left(RevWalkIterator {
                 repo: self.repo,
                 revwalk,
-            };
-            itertools::Either::Left(revwalk)
+            })
         } else {
-            itertools::Either::Right(Some(self.from.clone()).into_iter())
+            itertools::Either::Right(
+                self.include
+                    .iter()
+                    .filter_map(|id| self.repo.find_commit(*id).ok()),
+            )
+        };
+        Ok(commits
+            .filter(|commit| self.limits.contains(commit))
+            .take(self.limits.max_count.unwrap_or(usize::MAX)))
+    }
+
+    /// Commits on `HEAD` since it branched from its upstream
+    ///
+    /// Without an upstream configured, this falls back to `origin/HEAD`, `main`, or `master`.
+    pub(crate) fn since_upstream(repo: &'r git2::Repository) -> Result<Self, anyhow::Error> {
+        let upstream = upstream(repo)?;
+        Self::parse(repo, &format!("{}..HEAD", upstream.id()))
+    }
+}
+
+fn peel_to_commit<'r>(
+    revspec: &str,
+    object: &git2::Object<'r>,
+) -> Result<git2::Commit<'r>, anyhow::Error> {
+    object.peel_to_commit().map_err(|_| {
+        let kind = object.kind().map(|kind| kind.str()).unwrap_or("object");
+        anyhow::format_err!("`{revspec}` is a {kind}, not a commit")
+    })
+}
+
+/// Parse a date for `--since` and `--until`
+///
+/// Accepts timestamps (`2024-06-01T12:00:00Z`), local dates and times (`2024-06-01`), and
+/// relative times (`2 weeks ago`).  Like git, relative times are in the past even without `ago`.
+pub(crate) fn parse_date(date: &str) -> Result<jiff::Timestamp, anyhow::Error> {
+    if let Ok(timestamp) = date.parse::<jiff::Timestamp>() {
+        return Ok(timestamp);
+    }
+    if let Ok(datetime) = date.parse::<jiff::civil::DateTime>() {
+        return Ok(datetime.to_zoned(jiff::tz::TimeZone::system())?.timestamp());
+    }
+    if let Ok(span) = date.parse::<jiff::Span>() {
+        let span = if span.is_positive() {
+            span.negate()
+        } else {
+            span
+        };
+        return Ok(jiff::Zoned::now().checked_add(span)?.timestamp());
+    }
+    anyhow::bail!("expected a date like `2024-06-01` or `2 weeks ago`")
+}
+
+/// Annotated tags with names matching `pattern`, skipping lightweight tags
+pub(crate) fn annotated_tags<'r>(
+    repo: &'r git2::Repository,
+    pattern: &str,
+) -> Result<Vec<git2::Tag<'r>>, anyhow::Error> {
+    let mut tags = Vec::new();
+    for name in repo.tag_names(Some(pattern))?.iter() {
+        let Some(name) = name? else {
+            continue;
+        };
+        let reference = repo.find_reference(&format!("refs/tags/{name}"))?;
+        match reference.target().map(|id| repo.find_tag(id)) {
+            Some(Ok(tag)) => tags.push(tag),
+            _ => log::debug!("Skipping lightweight tag {name}"),
         }
     }
+    Ok(tags)
+}
+
+/// Name of the branch checked out at `HEAD`
+pub(crate) fn current_branch(repo: &git2::Repository) -> Result<String, anyhow::Error> {
+    let head = repo.head()?;
+    if !head.is_branch() {
+        anyhow::bail!("`HEAD` is not on a branch, pass a branch name to `--branch`");
+    }
+    Ok(String::from_utf8_lossy(head.shorthand_bytes()).into_owned())
+}
+
+const UPSTREAM_FALLBACKS: &[&str] = &[
+    "refs/remotes/origin/HEAD",
+    "refs/heads/main",
+    "refs/heads/master",
+];
+
+fn upstream(repo: &git2::Repository) -> Result<git2::Commit<'_>, anyhow::Error> {
+    let head = repo.head()?;
+    if head.is_branch() {
+        match git2::Branch::wrap(head).upstream() {
+            Ok(upstream) => {
+                log::info!(
+                    "Checking since upstream {}",
+                    String::from_utf8_lossy(upstream.get().shorthand_bytes())
+                );
+                return Ok(upstream.get().peel_to_commit()?);
+            }
+            Err(err) => {
+                log::debug!("No upstream for `HEAD`: {}", err.message());
+            }
+        }
+    }
+    for name in UPSTREAM_FALLBACKS {
+        if let Ok(reference) = repo.find_reference(name) {
+            log::info!(
+                "Checking since {}",
+                String::from_utf8_lossy(reference.shorthand_bytes())
+            );
+            return Ok(reference.peel_to_commit()?);
+        }
+    }
+    anyhow::bail!(
+        "could not find an upstream for `HEAD`, tried {}",
+        UPSTREAM_FALLBACKS.join(", ")
+    )
 }
 
 struct RevWalkIterator<'r> {
@@ -55,3 +330,42 @@ impl<'r> Iterator for RevWalkIterator<'r> {
         }
     }
 }
+
+/// Look up the commits for `ignore_commits`, warning about those not in the repo
+pub(crate) fn resolve_ignore_commits<'c>(
+    repo: &git2::Repository,
+    ignore_commits: &'c [crate::config::IgnoreCommit],
+) -> Vec<(git2::Oid, &'c crate::config::IgnoreCommit)> {
+    ignore_commits
+        .iter()
+        .filter_map(|ignore| match repo.find_commit_by_prefix(ignore.commit()) {
+            Ok(commit) => Some((commit.id(), ignore)),
+            Err(err) => {
+                log::warn!(
+                    "Skipping `ignore_commits` entry `{}`: {}",
+                    ignore.commit(),
+                    err.message()
+                );
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_dates() {
+        assert_eq!(
+            parse_date("2024-06-01T12:00:00Z").unwrap().as_second(),
+            1717243200
+        );
+        assert!(parse_date("2024-06-01").is_ok());
+        assert!(parse_date("2 weeks ago").unwrap() < jiff::Timestamp::now());
+        assert!(parse_date("2 weeks").unwrap() < jiff::Timestamp::now());
+        assert!(parse_date("3d").unwrap() < jiff::Timestamp::now());
+        assert!(parse_date("yesterdayish").is_err());
+    }
+}
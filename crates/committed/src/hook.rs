@@ -0,0 +1,257 @@
+use crate::config::Config;
+use crate::config::Style;
+use crate::git::RevSpec;
+
+/// A ref being updated, as git passes to server-side hooks
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct RefUpdate {
+    pub(crate) old: git2::Oid,
+    pub(crate) new: git2::Oid,
+    pub(crate) name: String,
+}
+
+impl RefUpdate {
+    pub(crate) fn is_delete(&self) -> bool {
+        self.new.is_zero()
+    }
+
+    pub(crate) fn is_create(&self) -> bool {
+        self.old.is_zero()
+    }
+
+    /// Commits the update brings in
+    ///
+    /// For a new ref, or when the old commit isn't known locally, these are the commits not
+    /// reachable from the refs matching `existing`.
+    pub(crate) fn revspec<'r>(
+        &self,
+        repo: &'r git2::Repository,
+        existing: &str,
+    ) -> Result<RevSpec<'r>, anyhow::Error> {
+        let mut revspec = RevSpec::new(repo);
+        revspec.push_id(self.new)?;
+        if !self.is_create() && repo.find_object(self.old, None).is_ok() {
+            revspec.hide_id(self.old)?;
+        } else {
+            revspec.hide_references(existing)?;
+        }
+        Ok(revspec)
+    }
+}
+
+/// Parse the `<old> <new> <ref>` lines `pre-receive` reads from stdin
+pub(crate) fn parse_pre_receive(text: &str) -> Result<Vec<RefUpdate>, anyhow::Error> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let invalid = || anyhow::format_err!("invalid `pre-receive` line `{line}`");
+            let mut fields = line.split_whitespace();
+            let (Some(old), Some(new), Some(name), None) =
+                (fields.next(), fields.next(), fields.next(), fields.next())
+            else {
+                return Err(invalid());
+            };
+            Ok(RefUpdate {
+                old: git2::Oid::from_str(old).map_err(|_| invalid())?,
+                new: git2::Oid::from_str(new).map_err(|_| invalid())?,
+                name: name.to_owned(),
+            })
+        })
+        .collect()
+}
+
+/// Parse the `<local ref> <local sha> <remote ref> <remote sha>` lines `pre-push` reads from stdin
+///
+/// The updates are to the remote refs.
+pub(crate) fn parse_pre_push(text: &str) -> Result<Vec<RefUpdate>, anyhow::Error> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let invalid = || anyhow::format_err!("invalid `pre-push` line `{line}`");
+            let mut fields = line.split_whitespace();
+            let (Some(_local_ref), Some(local), Some(remote_ref), Some(remote), None) = (
+                fields.next(),
+                fields.next(),
+                fields.next(),
+                fields.next(),
+                fields.next(),
+            ) else {
+                return Err(invalid());
+            };
+            Ok(RefUpdate {
+                old: git2::Oid::from_str(remote).map_err(|_| invalid())?,
+                new: git2::Oid::from_str(local).map_err(|_| invalid())?,
+                name: remote_ref.to_owned(),
+            })
+        })
+        .collect()
+}
+
+/// Refs holding what is already on `remote`, like `refs/remotes/origin/*`
+///
+/// When pushing to a URL rather than a named remote, all remote-tracking refs are used.
+pub(crate) fn remote_tracking_glob(repo: &git2::Repository, remote: &str) -> String {
+    if repo.find_remote(remote).is_ok() {
+        format!("refs/remotes/{remote}/*")
+    } else {
+        "refs/remotes/*".to_owned()
+    }
+}
+
+/// Whether `prepare-commit-msg` should add a template for git's message source
+///
+/// Messages from `-m`, merges, squashes, and `--amend` or `-c` are left alone.
+pub(crate) fn wants_template(source: Option<&str>) -> bool {
+    matches!(source, None | Some("template"))
+}
+
+/// Insert `#` comments describing `config` ahead of git's own comments in a commit message
+pub(crate) fn add_template(message: &str, config: &Config) -> String {
+    let template = template(config);
+    if template.is_empty() {
+        return message.to_owned();
+    }
+    let index = message
+        .match_indices('#')
+        .map(|(index, _)| index)
+        .find(|index| *index == 0 || message[..*index].ends_with('\n'));
+    match index {
+        Some(index) => format!("{}{template}#\n{}", &message[..index], &message[index..]),
+        None if message.is_empty() || message.ends_with('\n') => format!("{message}{template}"),
+        None => format!("{message}\n{template}"),
+    }
+}
+
+fn template(config: &Config) -> String {
+    let mut lines = Vec::new();
+    if config.style() == Style::Conventional {
+        lines.push("Format: <type>(<scope>): <description>".to_owned());
+        let types = config.allowed_types().collect::<Vec<_>>();
+        if !types.is_empty() {
+            lines.push(format!("Types: {}", types.join(", ")));
+        }
+        let scopes = config.allowed_scopes().collect::<Vec<_>>();
+        if !scopes.is_empty() {
+            lines.push(format!("Scopes: {}", scopes.join(", ")));
+        }
+    }
+    if config.subject_length() != 0 {
+        lines.push(format!(
+            "Subject: at most {} characters",
+            config.subject_length()
+        ));
+    }
+    if config.line_length() != 0 {
+        lines.push(format!("Body: wrap at {} characters", config.line_length()));
+    }
+    lines.iter().map(|line| format!("# {line}\n")).collect()
+}
+
+/// `config` with the `ref_rules` for `name` applied
+pub(crate) fn ref_config(config: &Config, name: &str) -> Config {
+    let mut merged = config.clone();
+    for (pattern, rules) in config.ref_rules() {
+        if glob_matches(pattern, name) {
+            log::debug!("Using `ref_rules` for `{pattern}` with {name}");
+            merged.update(rules.clone());
+        }
+    }
+    merged
+}
+
+/// Match a ref name against a glob where `*` matches any characters, including `/`
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    let re = regex::escape(pattern)
+        .replace(r"\*", ".*")
+        .replace(r"\?", ".");
+    regex::Regex::new(&format!("^{re}$")).is_ok_and(|re| re.is_match(name))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pre_receive_lines() {
+        let zero = "0000000000000000000000000000000000000000";
+        let id = "0123456789abcdef0123456789abcdef01234567";
+        let updates = parse_pre_receive(&format!(
+            "{zero} {id} refs/heads/topic\n{id} {zero} refs/heads/old\n"
+        ))
+        .unwrap();
+        assert_eq!(updates.len(), 2);
+        assert!(updates[0].is_create());
+        assert!(updates[1].is_delete());
+        assert_eq!(updates[1].name, "refs/heads/old");
+
+        assert!(parse_pre_receive(&format!("{id} refs/heads/main")).is_err());
+    }
+
+    #[test]
+    fn pre_push_lines() {
+        let zero = "0000000000000000000000000000000000000000";
+        let id = "0123456789abcdef0123456789abcdef01234567";
+        let updates = parse_pre_push(&format!(
+            "refs/heads/topic {id} refs/heads/feature {zero}\n(delete) {zero} refs/heads/old {id}\n"
+        ))
+        .unwrap();
+        assert_eq!(updates.len(), 2);
+        assert!(updates[0].is_create());
+        assert_eq!(updates[0].name, "refs/heads/feature");
+        assert!(updates[1].is_delete());
+
+        assert!(parse_pre_push(&format!("{zero} {id} refs/heads/main")).is_err());
+    }
+
+    #[test]
+    fn commit_template() {
+        let config = Config {
+            style: Some(Style::Conventional),
+            allowed_types: Some(vec!["feat".to_owned(), "fix".to_owned()]),
+            allowed_scopes: Some(vec!["parser".to_owned()]),
+            ..Default::default()
+        };
+        let message = "\n# Please enter the commit message for your changes.\n";
+        assert_eq!(
+            add_template(message, &config),
+            "
+# Format: <type>(<scope>): <description>
+# Types: feat, fix
+# Scopes: parser
+# Subject: at most 50 characters
+# Body: wrap at 72 characters
+#
+# Please enter the commit message for your changes.
+"
+        );
+
+        let config = Config {
+            subject_length: Some(0),
+            line_length: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(add_template(message, &config), message);
+    }
+
+    #[test]
+    fn template_sources() {
+        assert!(wants_template(None));
+        assert!(wants_template(Some("template")));
+        assert!(!wants_template(Some("message")));
+        assert!(!wants_template(Some("merge")));
+        assert!(!wants_template(Some("squash")));
+        assert!(!wants_template(Some("commit")));
+    }
+
+    #[test]
+    fn ref_globs() {
+        assert!(glob_matches("refs/heads/main", "refs/heads/main"));
+        assert!(!glob_matches("refs/heads/main", "refs/heads/main-old"));
+        assert!(glob_matches(
+            "refs/heads/release/*",
+            "refs/heads/release/1.0"
+        ));
+        assert!(glob_matches("refs/tags/v*", "refs/tags/v1.0.0"));
+        assert!(!glob_matches("refs/tags/v*", "refs/heads/v1"));
+    }
+}
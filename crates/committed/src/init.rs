@@ -0,0 +1,229 @@
+use crate::checks;
+use crate::config::Config;
+use crate::config::Style;
+use crate::report;
+
+/// Portion of commits a proposed rule should pass to be enabled
+const COVERAGE: f64 = 0.9;
+
+/// Observations about recent commit messages
+#[derive(Debug, Default)]
+pub(crate) struct History {
+    total: usize,
+    conventional: usize,
+    types: Vec<(String, usize)>,
+    scopes: Vec<(String, usize)>,
+    subject_lengths: Vec<usize>,
+    line_lengths: Vec<usize>,
+    capitalized: usize,
+    not_punctuated: usize,
+    imperative: usize,
+}
+
+impl History {
+    pub(crate) fn from_messages<'m>(messages: impl IntoIterator<Item = &'m str>) -> Self {
+        let mut history = Self::default();
+        for message in messages {
+            history.add(message);
+        }
+        history
+    }
+
+    fn add(&mut self, message: &str) {
+        let message = checks::strip_fixup(checks::strip_wip(message.trim()));
+        if message.is_empty() {
+            return;
+        }
+        self.total += 1;
+
+        let source = report::Source::from(std::path::Path::new("-"));
+        let subject = match committed::conventional::Commit::parse(message) {
+            Ok(parsed) => {
+                self.conventional += 1;
+                count(&mut self.types, parsed.type_().as_str());
+                if let Some(scope) = parsed.scope() {
+                    count(&mut self.scopes, scope.as_str());
+                }
+                parsed.description()
+            }
+            Err(_) => message.lines().next().unwrap_or_default(),
+        };
+
+        let first_line = message.lines().next().unwrap_or_default().trim_end();
+        self.subject_lengths.push(graphemes(first_line));
+        let longest_line = message
+            .lines()
+            .map(|line| graphemes(line.trim_end()))
+            .max()
+            .unwrap_or_default();
+        self.line_lengths.push(longest_line);
+
+        if matches!(
+            checks::check_capitalized_subject(source, subject, &report::print_silent),
+            Ok(false)
+        ) {
+            self.capitalized += 1;
+        }
+        if matches!(
+            checks::check_subject_not_punctuated(source, subject, &report::print_silent),
+            Ok(false)
+        ) {
+            self.not_punctuated += 1;
+        }
+        let vocabulary = checks::Vocabulary::default();
+        if matches!(
+            checks::check_imperative_subject(source, subject, &vocabulary, &report::print_silent),
+            Ok(false)
+        ) {
+            self.imperative += 1;
+        }
+    }
+
+    pub(crate) fn total(&self) -> usize {
+        self.total
+    }
+
+    /// A config that the bulk of the observed history passes
+    pub(crate) fn propose(&self) -> Config {
+        let defaults = Config::default();
+        let style = if self.mostly(self.conventional) {
+            Style::Conventional
+        } else {
+            Style::None
+        };
+        let (allowed_types, allowed_scopes) = if style == Style::Conventional {
+            let scopes = names(&self.scopes);
+            (
+                Some(names(&self.types)),
+                (!scopes.is_empty()).then_some(scopes),
+            )
+        } else {
+            (None, None)
+        };
+        Config {
+            style: Some(style),
+            allowed_types,
+            allowed_scopes,
+            subject_length: Some(
+                percentile(&self.subject_lengths, COVERAGE).max(defaults.subject_length()),
+            ),
+            line_length: Some(percentile(&self.line_lengths, COVERAGE).max(defaults.line_length())),
+            subject_capitalized: Some(self.mostly(self.capitalized)),
+            subject_not_punctuated: Some(self.mostly(self.not_punctuated)),
+            imperative_subject: Some(self.mostly(self.imperative)),
+            ..Default::default()
+        }
+    }
+
+    fn mostly(&self, count: usize) -> bool {
+        0 < self.total && COVERAGE <= count as f64 / self.total as f64
+    }
+}
+
+fn count(counts: &mut Vec<(String, usize)>, name: &str) {
+    let name = name.to_lowercase();
+    if let Some((_, n)) = counts.iter_mut().find(|(existing, _)| *existing == name) {
+        *n += 1;
+    } else {
+        counts.push((name, 1));
+    }
+}
+
+/// Names, most used first
+fn names(counts: &[(String, usize)]) -> Vec<String> {
+    let mut counts = counts.to_vec();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts.into_iter().map(|(name, _)| name).collect()
+}
+
+fn graphemes(line: &str) -> usize {
+    unicode_segmentation::UnicodeSegmentation::graphemes(line, true).count()
+}
+
+fn percentile(values: &[usize], portion: f64) -> usize {
+    if values.is_empty() {
+        return 0;
+    }
+    let mut values = values.to_vec();
+    values.sort_unstable();
+    let index = ((values.len() as f64 * portion).ceil() as usize).clamp(1, values.len()) - 1;
+    values[index]
+}
+
+/// Render a proposed config as a `committed.toml`
+pub(crate) fn render(history: &History, config: &Config) -> Result<String, anyhow::Error> {
+    let body = toml::to_string_pretty(config)?;
+    Ok(format!(
+        "# Generated by `committed init` from the last {} commits\n{body}",
+        history.total()
+    ))
+}
+
+/// Messages of the last `max_count` commits reachable from `HEAD`
+pub(crate) fn recent_messages(
+    repo: &git2::Repository,
+    max_count: usize,
+) -> Result<Vec<String>, anyhow::Error> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_head()?;
+    let mut messages = Vec::new();
+    for id in revwalk {
+        if messages.len() == max_count {
+            break;
+        }
+        let commit = repo.find_commit(id?)?;
+        if 1 < commit.parent_count() {
+            continue;
+        }
+        if let Ok(message) = commit.message() {
+            messages.push(message.to_owned());
+        }
+    }
+    Ok(messages)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn propose_conventional() {
+        let history = History::from_messages([
+            "feat(parser): Parse greetings",
+            "fix(parser): Handle empty greetings",
+            "docs: Describe greetings",
+            "feat: Wave goodbye",
+        ]);
+        let config = history.propose();
+        assert_eq!(config.style(), Style::Conventional);
+        assert_eq!(
+            config.allowed_types().collect::<Vec<_>>(),
+            ["feat", "docs", "fix"]
+        );
+        assert_eq!(config.allowed_scopes().collect::<Vec<_>>(), ["parser"]);
+        assert!(config.subject_capitalized());
+        assert!(config.imperative_subject());
+    }
+
+    #[test]
+    fn propose_plain() {
+        let history = History::from_messages([
+            "updated the readme.",
+            "Add a subject that goes on and on and on and on and on and on",
+            "fixed the build",
+        ]);
+        let config = history.propose();
+        assert_eq!(config.style(), Style::None);
+        assert_eq!(config.allowed_types, None);
+        assert_eq!(config.subject_length(), 61);
+        assert!(!config.subject_capitalized());
+        assert!(!config.subject_not_punctuated());
+    }
+
+    #[test]
+    fn percentile_covers_most() {
+        assert_eq!(percentile(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 100], 0.9), 9);
+        assert_eq!(percentile(&[5], 0.9), 5);
+        assert_eq!(percentile(&[], 0.9), 0);
+    }
+}
@@ -0,0 +1,155 @@
+/// Marks hooks written by `committed install-hooks`
+const MARKER: &str = "# Installed by `committed install-hooks`";
+
+/// Suffix for an existing hook that is kept and run before `committed`
+const CHAINED_SUFFIX: &str = ".pre-committed";
+
+/// A client-side hook that `committed` can install
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Hook {
+    CommitMsg,
+    PrepareCommitMsg,
+    PrePush,
+}
+
+impl Hook {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::CommitMsg => "commit-msg",
+            Self::PrepareCommitMsg => "prepare-commit-msg",
+            Self::PrePush => "pre-push",
+        }
+    }
+
+    fn command(self) -> &'static str {
+        match self {
+            // Like `.pre-commit-hooks.yaml`, leave fixup and WIP commits for `rebase` to catch
+            Self::CommitMsg => r#"committed --fixup --wip --commit-file "$1""#,
+            Self::PrepareCommitMsg => r#"committed hook prepare-commit-msg "$@""#,
+            Self::PrePush => r#"committed hook pre-push "$@""#,
+        }
+    }
+
+    /// Whether git passes the hook input on stdin, which the chained hook needs too
+    fn reads_stdin(self) -> bool {
+        match self {
+            Self::CommitMsg | Self::PrepareCommitMsg => false,
+            Self::PrePush => true,
+        }
+    }
+
+    fn script(self) -> String {
+        let command = self.command();
+        if self.reads_stdin() {
+            format!(
+                r#"#!/bin/sh
+{MARKER}
+input=$(cat)
+chained="$0{CHAINED_SUFFIX}"
+if [ -x "$chained" ]; then
+    printf '%s\n' "$input" | "$chained" "$@" || exit $?
+fi
+printf '%s\n' "$input" | {command}
+"#
+            )
+        } else {
+            format!(
+                r#"#!/bin/sh
+{MARKER}
+chained="$0{CHAINED_SUFFIX}"
+if [ -x "$chained" ]; then
+    "$chained" "$@" || exit $?
+fi
+exec {command}
+"#
+            )
+        }
+    }
+}
+
+/// Where git looks for hooks, respecting `core.hooksPath`
+///
+/// Worktrees share the hooks of the main repo.
+pub(crate) fn hooks_dir(repo: &git2::Repository) -> Result<std::path::PathBuf, anyhow::Error> {
+    let config = repo.config()?;
+    match config.get_path("core.hooksPath") {
+        Ok(path) if path.is_absolute() => Ok(path),
+        // Hooks run from the root of the work tree
+        Ok(path) => Ok(repo.workdir().unwrap_or_else(|| repo.path()).join(path)),
+        Err(_) => Ok(repo.commondir().join("hooks")),
+    }
+}
+
+/// Write `hook` into `dir`, keeping any existing hook to run first
+pub(crate) fn install(dir: &std::path::Path, hook: Hook) -> Result<(), anyhow::Error> {
+    std::fs::create_dir_all(dir)
+        .map_err(|err| anyhow::format_err!("could not create {}: {err}", dir.display()))?;
+    let path = dir.join(hook.name());
+    if path.exists() && !is_installed(&path) {
+        let chained = chained_path(&path);
+        if chained.exists() {
+            anyhow::bail!(
+                "{} already exists, remove it or {} to install",
+                chained.display(),
+                path.display()
+            );
+        }
+        std::fs::rename(&path, &chained)
+            .map_err(|err| anyhow::format_err!("could not move {}: {err}", path.display()))?;
+        log::info!("Moved the existing hook to {}", chained.display());
+    }
+    std::fs::write(&path, hook.script())
+        .map_err(|err| anyhow::format_err!("could not write {}: {err}", path.display()))?;
+    set_executable(&path)?;
+    log::info!("Installed {}", path.display());
+    Ok(())
+}
+
+/// Remove `hook` from `dir`, restoring any hook it ran first
+pub(crate) fn uninstall(dir: &std::path::Path, hook: Hook) -> Result<(), anyhow::Error> {
+    let path = dir.join(hook.name());
+    if !path.exists() {
+        log::debug!("Skipping {}, not installed", path.display());
+        return Ok(());
+    }
+    if !is_installed(&path) {
+        log::warn!(
+            "Skipping {}, it was not installed by `committed`",
+            path.display()
+        );
+        return Ok(());
+    }
+    std::fs::remove_file(&path)
+        .map_err(|err| anyhow::format_err!("could not remove {}: {err}", path.display()))?;
+    let chained = chained_path(&path);
+    if chained.exists() {
+        std::fs::rename(&chained, &path)
+            .map_err(|err| anyhow::format_err!("could not restore {}: {err}", path.display()))?;
+        log::info!("Restored the original {}", path.display());
+    } else {
+        log::info!("Removed {}", path.display());
+    }
+    Ok(())
+}
+
+fn is_installed(path: &std::path::Path) -> bool {
+    std::fs::read_to_string(path).is_ok_and(|script| script.contains(MARKER))
+}
+
+fn chained_path(path: &std::path::Path) -> std::path::PathBuf {
+    let mut chained = path.as_os_str().to_owned();
+    chained.push(CHAINED_SUFFIX);
+    chained.into()
+}
+
+#[cfg(unix)]
+fn set_executable(path: &std::path::Path) -> Result<(), anyhow::Error> {
+    use std::os::unix::fs::PermissionsExt as _;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+        .map_err(|err| anyhow::format_err!("could not make {} executable: {err}", path.display()))
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &std::path::Path) -> Result<(), anyhow::Error> {
+    Ok(())
+}
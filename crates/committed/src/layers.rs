@@ -0,0 +1,415 @@
+use crate::config::Config;
+
+/// Files that may hold config within a directory, highest precedence first
+const CONFIG_FILENAMES: [&str; 5] = [
+    "committed.toml",
+    ".committed.toml",
+    "Cargo.toml",
+    "pyproject.toml",
+    "package.json",
+];
+
+pub(crate) const CONFIG_ENV: &str = "COMMITTED_CONFIG";
+
+/// Where a layer of configuration came from
+#[derive(Clone, Debug, PartialEq, Eq, derive_more::Display)]
+#[non_exhaustive]
+pub(crate) enum Origin {
+    #[display("default")]
+    Default,
+    #[display("user config {}", _0.display())]
+    User(std::path::PathBuf),
+    #[display("repo config {}", _0.display())]
+    Repo(std::path::PathBuf),
+    #[display("`--config` {}", _0.display())]
+    Arg(std::path::PathBuf),
+    #[display("`${CONFIG_ENV}` {}", _0.display())]
+    Env(std::path::PathBuf),
+    #[display("command-line")]
+    CommandLine,
+}
+
+/// Configuration sources, from lowest to highest precedence
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Layers {
+    layers: Vec<(Origin, Config)>,
+}
+
+impl Layers {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn push(&mut self, origin: Origin, config: Config) {
+        match origin {
+            Origin::Default | Origin::CommandLine => log::debug!("Using {origin}"),
+            _ => log::info!("Using {origin}"),
+        }
+        self.layers.push((origin, config));
+    }
+
+    pub(crate) fn merged(&self) -> Config {
+        let mut merged = Config::default();
+        for (_, config) in &self.layers {
+            merged.update(config.clone());
+        }
+        merged
+    }
+
+    /// The layer that last set `key`
+    pub(crate) fn origin(&self, key: &str) -> Result<&Origin, anyhow::Error> {
+        for (origin, config) in self.layers.iter().rev() {
+            let table = toml::Table::try_from(config)?;
+            if table.contains_key(key) {
+                return Ok(origin);
+            }
+        }
+        Ok(&Origin::Default)
+    }
+}
+
+/// Load a config file, falling back to defaults if it has no `committed` section
+pub(crate) fn load_file(path: &std::path::Path) -> Result<Config, anyhow::Error> {
+    let config = find_config(path)?.unwrap_or_else(|| {
+        log::warn!("No `committed` config found in {}", path.display());
+        Config::default()
+    });
+    Ok(config)
+}
+
+/// Load the `committed` section of a config file, if present, resolving any `extends`
+///
+/// Besides standalone config files, this reads
+/// - `Cargo.toml`: `[workspace.metadata.committed]` and `[package.metadata.committed]`
+/// - `pyproject.toml`: `[tool.committed]`
+/// - `package.json`: `"committed"`
+pub(crate) fn find_config(path: &std::path::Path) -> Result<Option<Config>, anyhow::Error> {
+    let Some(config) = read_config(path)? else {
+        return Ok(None);
+    };
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_owned());
+    let mut chain = vec![path.display().to_string()];
+    let config = resolve_extends(config, path.parent(), &mut chain)?;
+    Ok(Some(config))
+}
+
+fn read_config(path: &std::path::Path) -> Result<Option<Config>, anyhow::Error> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("could not read {}: {e}", path.display()))?;
+    let filename = path.file_name().and_then(|n| n.to_str());
+    let config = match filename {
+        Some("Cargo.toml") => parse_cargo_toml(&text),
+        Some("pyproject.toml") => parse_pyproject_toml(&text),
+        Some("package.json") => parse_package_json(&text),
+        _ => parse_toml(&text).map(Some),
+    };
+    config.map_err(|e| config_error(path, &e))
+}
+
+/// Merge each entry of `extends` underneath `config`, in order
+///
+/// `chain` holds the files and presets currently being resolved, ending with the one `config`
+/// came from, for cycle detection and error reporting.  `base_dir` is where relative paths are
+/// resolved from, if `config` came from a file.
+fn resolve_extends(
+    mut config: Config,
+    base_dir: Option<&std::path::Path>,
+    chain: &mut Vec<String>,
+) -> Result<Config, anyhow::Error> {
+    let Some(extends) = config.extends.take() else {
+        return Ok(config);
+    };
+    let current = chain.last().cloned().unwrap_or_default();
+
+    let mut resolved = Config::default();
+    for entry in extends {
+        let (name, base, next_base_dir) = if let Some(preset) =
+            entry.strip_prefix(crate::presets::PRESET_PREFIX)
+        {
+            let text = crate::presets::find(preset).ok_or_else(|| {
+                let presets = crate::presets::names()
+                    .map(|name| format!("`{}{name}`", crate::presets::PRESET_PREFIX))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                anyhow::anyhow!("{current}: unknown preset `{entry}`, expected one of {presets}")
+            })?;
+            let base =
+                parse_toml(text).map_err(|e| anyhow::anyhow!("invalid preset `{entry}`: {e}"))?;
+            (entry, base, None)
+        } else {
+            let Some(base_dir) = base_dir else {
+                anyhow::bail!("{current}: presets cannot extend files, found `{entry}`");
+            };
+            let path = base_dir.join(&entry);
+            let path = path.canonicalize().map_err(|e| {
+                anyhow::anyhow!(
+                    "{current}: could not find `{entry}` ({}): {e}",
+                    path.display()
+                )
+            })?;
+            let base = read_config(&path)?.ok_or_else(|| {
+                anyhow::anyhow!(
+                    "{current}: extended file {} has no `committed` config",
+                    path.display()
+                )
+            })?;
+            let next_base_dir = path.parent().map(|p| p.to_owned());
+            (path.display().to_string(), base, next_base_dir)
+        };
+
+        if chain.contains(&name) {
+            anyhow::bail!(
+                "{current}: `extends` forms a cycle: {} -> {name}",
+                chain.join(" -> ")
+            );
+        }
+        chain.push(name);
+        let base = resolve_extends(base, next_base_dir.as_deref(), chain)?;
+        chain.pop();
+
+        resolved.update(base);
+    }
+    resolved.update(config);
+    Ok(resolved)
+}
+
+fn parse_toml(text: &str) -> Result<Config, anyhow::Error> {
+    toml::from_str(text).map_err(|e| e.into())
+}
+
+#[derive(serde::Deserialize)]
+struct CargoManifest {
+    workspace: Option<CargoMetadata>,
+    package: Option<CargoMetadata>,
+}
+
+#[derive(serde::Deserialize)]
+struct CargoMetadata {
+    metadata: Option<Section>,
+}
+
+#[derive(serde::Deserialize)]
+struct PyProject {
+    tool: Option<Section>,
+}
+
+#[derive(serde::Deserialize)]
+struct Section {
+    committed: Option<Config>,
+}
+
+fn parse_cargo_toml(text: &str) -> Result<Option<Config>, anyhow::Error> {
+    let manifest: CargoManifest = toml::from_str(text)?;
+    let mut config = None;
+    for table in [manifest.workspace, manifest.package] {
+        let Some(section) = table.and_then(|t| t.metadata).and_then(|m| m.committed) else {
+            continue;
+        };
+        config.get_or_insert_with(Config::default).update(section);
+    }
+    Ok(config)
+}
+
+fn parse_pyproject_toml(text: &str) -> Result<Option<Config>, anyhow::Error> {
+    let pyproject: PyProject = toml::from_str(text)?;
+    Ok(pyproject.tool.and_then(|t| t.committed))
+}
+
+fn parse_package_json(text: &str) -> Result<Option<Config>, anyhow::Error> {
+    let package: Section = serde_json::from_str(text)?;
+    Ok(package.committed)
+}
+
+static UNKNOWN_FIELD_RE: std::sync::LazyLock<regex::Regex> = std::sync::LazyLock::new(|| {
+    regex::Regex::new(r"unknown (?:field|rule) `([^`]*)`, expected (?:one of )?(.*)").unwrap()
+});
+
+/// Suggest the closest known field or rule for a misspelled one
+fn suggest_field(message: &str) -> Option<String> {
+    let captures = UNKNOWN_FIELD_RE.captures(message)?;
+    let unknown = captures.get(1)?.as_str();
+    let expected = captures.get(2)?.as_str();
+    let expected = expected
+        .split(", ")
+        .map(|field| field.trim().trim_matches('`'));
+    closest(unknown, expected).map(|field| field.to_owned())
+}
+
+/// The candidate `unknown` is most likely a misspelling of, if any
+pub(crate) fn closest<'c>(
+    unknown: &str,
+    candidates: impl IntoIterator<Item = &'c str>,
+) -> Option<&'c str> {
+    candidates
+        .into_iter()
+        .map(|candidate| (strsim::jaro_winkler(unknown, candidate), candidate))
+        .filter(|(confidence, _)| 0.8 < *confidence)
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, candidate)| candidate)
+}
+
+fn config_error(path: &std::path::Path, error: &anyhow::Error) -> anyhow::Error {
+    let message = error.to_string();
+    let message = message.trim_end();
+    match suggest_field(message) {
+        Some(field) => anyhow::anyhow!(
+            "invalid config in {}: {message}\nhelp: did you mean `{field}`?",
+            path.display()
+        ),
+        None => anyhow::anyhow!("invalid config in {}: {message}", path.display()),
+    }
+}
+
+/// `$XDG_CONFIG_HOME/committed/committed.toml`, defaulting to `~/.config`
+pub(crate) fn user_config_path() -> Option<std::path::PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|v| !v.is_empty())
+        .map(std::path::PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .or_else(|| std::env::var_os("USERPROFILE"))
+                .filter(|v| !v.is_empty())
+                .map(|home| std::path::PathBuf::from(home).join(".config"))
+        })?;
+    Some(config_home.join("committed").join(CONFIG_FILENAMES[0]))
+}
+
+pub(crate) fn env_config_path() -> Option<std::path::PathBuf> {
+    std::env::var_os(CONFIG_ENV)
+        .filter(|v| !v.is_empty())
+        .map(std::path::PathBuf::from)
+}
+
+/// Config from the repo root down to `cwd`, lowest precedence first
+///
+/// Only the highest precedence file with `committed` config is used per directory.
+pub(crate) fn repo_configs(
+    workdir: &std::path::Path,
+    cwd: &std::path::Path,
+) -> Result<Vec<(std::path::PathBuf, Config)>, anyhow::Error> {
+    let workdir = workdir
+        .canonicalize()
+        .unwrap_or_else(|_| workdir.to_owned());
+    let cwd = cwd.canonicalize().unwrap_or_else(|_| cwd.to_owned());
+    let dirs: Vec<_> = if cwd.starts_with(&workdir) {
+        cwd.ancestors()
+            .take_while(|dir| dir.starts_with(&workdir))
+            .collect()
+    } else {
+        vec![workdir.as_path()]
+    };
+
+    let mut configs = Vec::new();
+    for dir in dirs.into_iter().rev() {
+        let mut used: Option<std::path::PathBuf> = None;
+        for path in CONFIG_FILENAMES
+            .iter()
+            .map(|name| dir.join(name))
+            .filter(|path| path.is_file())
+        {
+            if let Some(used) = used.as_ref() {
+                log::debug!("Ignoring {} in favor of {}", path.display(), used.display());
+                continue;
+            }
+            if let Some(config) = find_config(&path)? {
+                used = Some(path.clone());
+                configs.push((path, config));
+            } else {
+                log::trace!("No `committed` config found in {}", path.display());
+            }
+        }
+    }
+    Ok(configs)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn later_layers_win() {
+        let mut layers = Layers::new();
+        layers.push(
+            Origin::User("user.toml".into()),
+            Config {
+                subject_length: Some(60),
+                line_length: Some(80),
+                ..Default::default()
+            },
+        );
+        layers.push(
+            Origin::Repo("repo.toml".into()),
+            Config {
+                subject_length: Some(70),
+                ..Default::default()
+            },
+        );
+
+        let merged = layers.merged();
+        assert_eq!(merged.subject_length(), 70);
+        assert_eq!(merged.line_length(), 80);
+        assert_eq!(
+            layers.origin("subject_length").unwrap(),
+            &Origin::Repo("repo.toml".into())
+        );
+        assert_eq!(
+            layers.origin("line_length").unwrap(),
+            &Origin::User("user.toml".into())
+        );
+        assert_eq!(layers.origin("style").unwrap(), &Origin::Default);
+    }
+
+    #[test]
+    fn cargo_toml_package_overrides_workspace() {
+        let text = r#"
+[workspace.metadata.committed]
+style = "conventional"
+subject_length = 60
+
+[package.metadata.committed]
+subject_length = 70
+"#;
+        let config = parse_cargo_toml(text).unwrap().unwrap();
+        assert_eq!(config.style(), crate::config::Style::Conventional);
+        assert_eq!(config.subject_length(), 70);
+    }
+
+    #[test]
+    fn cargo_toml_without_config() {
+        let text = r#"
+[package]
+name = "foo"
+"#;
+        assert_eq!(parse_cargo_toml(text).unwrap(), None);
+    }
+
+    #[test]
+    fn pyproject_toml() {
+        let text = r#"
+[tool.committed]
+subject_length = 60
+"#;
+        let config = parse_pyproject_toml(text).unwrap().unwrap();
+        assert_eq!(config.subject_length(), 60);
+    }
+
+    #[test]
+    fn package_json() {
+        let text = r#"{"name": "foo", "committed": {"allowed_types": ["feat"]}}"#;
+        let config = parse_package_json(text).unwrap().unwrap();
+        assert_eq!(config.allowed_types().collect::<Vec<_>>(), ["feat"]);
+    }
+
+    #[test]
+    fn suggest_misspelled_field() {
+        let message =
+            "unknown field `subject_lenght`, expected one of `subject_length`, `line_length`";
+        assert_eq!(suggest_field(message).as_deref(), Some("subject_length"));
+    }
+
+    #[test]
+    fn no_suggestion_for_unrelated_field() {
+        let message = "unknown field `foo`, expected one of `subject_length`, `line_length`";
+        assert_eq!(suggest_field(message), None);
+    }
+}
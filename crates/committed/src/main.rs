@@ -6,11 +6,22 @@ use std::io::Write;
 use clap::Parser;
 use proc_exit::prelude::*;
 
+mod baseline;
 mod checks;
 mod color;
+mod compose;
 mod config;
+mod dump;
 mod git;
+mod hook;
+mod init;
+mod install;
+mod layers;
+mod patch;
+mod pr;
+mod presets;
 mod report;
+mod squash;
 
 const UNKNOWN_ERR: proc_exit::Code = proc_exit::Code::new(2);
 
@@ -19,9 +30,70 @@ const GIT_VERBOSE_MARKER: &str = "# ------------------------ >8 ----------------
 #[derive(Debug, Parser)]
 #[command(about, version)]
 #[command(group = clap::ArgGroup::new("mode").multiple(false))]
+#[command(group = clap::ArgGroup::new("walk").multiple(true).conflicts_with_all([
+    "commit_file", "dump_config", "patch", "squash", "pr_event", "branch", "tags",
+]))]
 struct Options {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     #[arg(group = "mode")]
-    commits: Option<String>,
+    /// Commits to check, like `HEAD`, `main..HEAD`, `^main HEAD`, or `main...HEAD`
+    commits: Vec<String>,
+
+    #[arg(long, group = "mode")]
+    /// Check the commits listed on stdin, one per line, like `git rev-list` output
+    stdin_revs: bool,
+
+    #[arg(long, group = "walk", conflicts_with = "since_upstream")]
+    /// Check all commits reachable from `HEAD` and any ref
+    all: bool,
+
+    #[arg(
+        long,
+        value_name = "GLOB",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "*",
+        group = "walk",
+        conflicts_with = "since_upstream",
+    )]
+    /// Check all commits reachable from branches matching the glob
+    branches: Option<String>,
+
+    #[arg(long, group = "walk")]
+    /// Check at most this many commits
+    max_count: Option<usize>,
+
+    #[arg(long, group = "walk")]
+    /// Only follow the first parent of merge commits, skipping the commits they bring in
+    first_parent: bool,
+
+    #[arg(long, group = "walk", conflicts_with = "merges_only")]
+    /// Skip merge commits
+    no_merges: bool,
+
+    #[arg(long, group = "walk")]
+    /// Only check merge commits
+    merges_only: bool,
+
+    #[arg(
+        long,
+        value_name = "DATE",
+        value_parser = git::parse_date,
+        group = "walk",
+    )]
+    /// Check commits more recent than the date, like `2024-06-01` or `2 weeks ago`
+    since: Option<jiff::Timestamp>,
+
+    #[arg(
+        long,
+        value_name = "DATE",
+        value_parser = git::parse_date,
+        group = "walk",
+    )]
+    /// Check commits older than the date, like `2024-06-01` or `2 weeks ago`
+    until: Option<jiff::Timestamp>,
 
     #[arg(long, group = "mode")]
     /// Check a message in a file with `-` for stdin
@@ -30,6 +102,39 @@ struct Options {
     #[arg(long, default_value = ".")]
     work_tree: std::path::PathBuf,
 
+    #[arg(long, value_name = "PATH", group = "mode")]
+    /// Check `git format-patch` output, either a `.patch` or mbox file or a directory of them
+    patch: Option<std::path::PathBuf>,
+
+    #[arg(long, value_name = "RANGE", group = "mode")]
+    /// Check the message that squashing the commits would produce, like `main..HEAD`
+    squash: Option<String>,
+
+    #[arg(long, value_name = "TITLE", requires = "squash")]
+    /// Subject for `--squash`, like a pull request title, rather than the first commit's subject
+    squash_title: Option<String>,
+
+    #[arg(long, requires = "squash")]
+    /// Write the message for `--squash` to stdout instead of checking it
+    print_squash: bool,
+
+    #[arg(long, value_name = "PATH", group = "mode")]
+    /// Check the title and body of a pull request from a GitHub or GitLab event payload
+    pr_event: Option<std::path::PathBuf>,
+
+    #[arg(long, value_name = "NAME", num_args = 0..=1, group = "mode")]
+    /// Check a branch name, defaulting to the current branch
+    branch: Option<Option<String>>,
+
+    #[arg(long, value_name = "PATTERN", group = "mode")]
+    /// Check the messages of annotated tags matching the pattern, like `v*`
+    tags: Option<String>,
+
+    #[arg(long, group = "mode")]
+    /// Check commits since `HEAD` branched from its upstream, falling back to `origin/HEAD`,
+    /// `main`, or `master`
+    since_upstream: bool,
+
     #[arg(long)]
     config: Option<std::path::PathBuf>,
 
@@ -37,6 +142,29 @@ struct Options {
     /// Write the current configuration to file with `-` for stdout
     dump_config: Option<std::path::PathBuf>,
 
+    #[arg(long, requires = "dump_config")]
+    /// Annotate `--dump-config` with where each value came from
+    show_origin: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "commit_file", "dump_config", "patch", "squash", "pr_event", "branch", "tags",
+            "write_baseline",
+        ],
+    )]
+    /// Skip findings recorded by `--write-baseline`
+    baseline: Option<std::path::PathBuf>,
+
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "commit_file", "dump_config", "patch", "squash", "pr_event", "branch", "tags",
+        ],
+    )]
+    /// Record the current findings to file, to grandfather them in with `--baseline`
+    write_baseline: Option<std::path::PathBuf>,
+
     #[arg(long, overrides_with("merge_commit"))]
     no_merge_commit: bool,
     #[arg(long, overrides_with("no_merge_commit"), hide(true))]
@@ -67,16 +195,120 @@ struct Options {
     verbose: clap_verbosity_flag::Verbosity,
 }
 
+#[derive(Debug, clap::Subcommand)]
+enum Command {
+    /// Inspect the configuration
+    #[command(subcommand)]
+    Config(ConfigCommand),
+    /// Propose a `committed.toml` based on the existing history
+    Init(InitArgs),
+    /// Write a commit message for the staged changes, prompting for each part
+    Commit(CommitArgs),
+    /// Install `committed` as the repo's `commit-msg` hook and, optionally, other hooks, keeping
+    /// any existing hooks
+    InstallHooks(InstallHooksArgs),
+    /// Remove the hooks written by `install-hooks`, restoring any they replaced
+    UninstallHooks,
+    /// Write the JSON schema for `committed.toml` to stdout
+    Schema,
+    /// Run as a git hook
+    #[command(subcommand)]
+    Hook(HookCommand),
+}
+
+#[derive(Debug, clap::Args)]
+struct InitArgs {
+    /// Number of recent commits to base the config on
+    #[arg(long, default_value_t = 100)]
+    max_count: usize,
+
+    /// Print the proposed config without writing it
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Overwrite an existing `committed.toml`
+    #[arg(long)]
+    force: bool,
+
+    /// Write the config without asking for confirmation
+    #[arg(long, short)]
+    yes: bool,
+}
+
+#[derive(Debug, clap::Args)]
+struct CommitArgs {
+    /// Print the message without committing
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[derive(Debug, clap::Args)]
+struct InstallHooksArgs {
+    /// Also check the commits new to a remote before pushing them
+    #[arg(long)]
+    pre_push: bool,
+
+    /// Also start commit messages with comments describing the config, like the allowed types
+    #[arg(long)]
+    prepare_commit_msg: bool,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum ConfigCommand {
+    /// Validate the configuration without checking any commits
+    Check,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum HookCommand {
+    /// Check the commits being pushed to a server, reading `<old> <new> <ref>` lines from stdin
+    PreReceive,
+    /// Check the commits new to a remote before pushing them, reading
+    /// `<local ref> <local sha> <remote ref> <remote sha>` lines from stdin
+    PrePush {
+        /// Name of the remote being pushed to
+        remote: String,
+        /// Location of the remote being pushed to
+        url: String,
+    },
+    /// Add comments describing the config, like the allowed types, to the message being written
+    PrepareCommitMsg {
+        /// File holding the commit message
+        file: std::path::PathBuf,
+        /// Where the message came from, like `message` for `-m`, skipping all but `template`
+        source: Option<String>,
+        /// Commit the message came from, for `--amend` or `-c`
+        commit: Option<String>,
+    },
+}
+
 impl Options {
     fn to_config(&self) -> config::Config {
         config::Config {
             merge_commit: self.merge_commit(),
             no_wip: self.wip().map(|b| !b),
             no_fixup: self.fixup().map(|b| !b),
+            since_upstream: self.since_upstream.then_some(true),
             ..Default::default()
         }
     }
 
+    fn limits(&self) -> git::Limits {
+        git::Limits {
+            max_count: self.max_count,
+            since: self.since,
+            until: self.until,
+            first_parent: self.first_parent,
+            merges: if self.no_merges {
+                git::Merges::Exclude
+            } else if self.merges_only {
+                git::Merges::Only
+            } else {
+                git::Merges::Include
+            },
+        }
+    }
+
     fn merge_commit(&self) -> Option<bool> {
         resolve_bool_arg(self.merge_commit, self.no_merge_commit)
     }
@@ -108,18 +340,23 @@ enum Format {
 }
 
 impl Format {
-    fn report(self) -> report::Report {
+    fn report(self) -> report::Report<'static> {
         match self {
-            Format::Silent => report::print_silent,
-            Format::Brief => report::print_brief,
-            Format::Json => report::print_json,
+            Format::Silent => &report::print_silent,
+            Format::Brief => &report::print_brief,
+            Format::Json => &report::print_json,
         }
     }
 }
 
-fn load_toml(path: &std::path::Path) -> Result<config::Config, anyhow::Error> {
-    let text = std::fs::read_to_string(path)?;
-    toml::from_str(&text).map_err(|e| e.into())
+/// `-v` shows warnings along with what `committed` is doing, like which config files are used
+fn log_level(verbose: &clap_verbosity_flag::Verbosity) -> Option<log::Level> {
+    match verbose.log_level()? {
+        log::Level::Error => Some(log::Level::Error),
+        log::Level::Warn => Some(log::Level::Info),
+        log::Level::Info => Some(log::Level::Debug),
+        log::Level::Debug | log::Level::Trace => Some(log::Level::Trace),
+    }
 }
 
 fn init_logging(level: Option<log::Level>) {
@@ -153,11 +390,22 @@ fn init_logging(level: Option<log::Level>) {
 }
 
 fn run() -> proc_exit::ExitResult {
-    let options = Options::parse();
+    use clap::CommandFactory as _;
+    use clap::FromArgMatches as _;
+    let matches = Options::command().get_matches();
+    let options = Options::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+    if options.command.is_some() && (matches.contains_id("mode") || matches.contains_id("walk")) {
+        Options::command()
+            .error(
+                clap::error::ErrorKind::ArgumentConflict,
+                "subcommands cannot be used with commits, `--commit-file`, `--all`, or other modes",
+            )
+            .exit();
+    }
 
     options.color.write_global();
 
-    init_logging(options.verbose.log_level());
+    init_logging(log_level(&options.verbose));
 
     let repo = || {
         let repo = options
@@ -168,28 +416,69 @@ fn run() -> proc_exit::ExitResult {
         git2::Repository::discover(repo).with_code(proc_exit::sysexits::USAGE_ERR)
     };
 
-    let mut config = if let Some(config_path) = options.config.as_ref() {
-        load_toml(config_path).with_code(proc_exit::sysexits::CONFIG_ERR)?
+    match options.command.as_ref() {
+        Some(Command::Init(args)) => return run_init(&repo()?, args),
+        Some(Command::InstallHooks(args)) => return run_install_hooks(&repo()?, Some(args)),
+        Some(Command::UninstallHooks) => return run_install_hooks(&repo()?, None),
+        Some(Command::Schema) => {
+            let output = config::schema_json().with_code(proc_exit::Code::FAILURE)?;
+            std::io::stdout()
+                .write_all(output.as_bytes())
+                .to_sysexits()?;
+            return Ok(());
+        }
+        Some(Command::Config(_) | Command::Hook(_) | Command::Commit(_)) | None => {}
+    }
+
+    let mut layers = layers::Layers::new();
+    if let Some(user_path) = layers::user_config_path()
+        && user_path.is_file()
+    {
+        let config = layers::load_file(&user_path).with_code(proc_exit::sysexits::CONFIG_ERR)?;
+        layers.push(layers::Origin::User(user_path), config);
+    }
+    if let Some(config_path) = options.config.as_ref() {
+        let config = layers::load_file(config_path).with_code(proc_exit::sysexits::CONFIG_ERR)?;
+        layers.push(layers::Origin::Arg(config_path.clone()), config);
     } else {
-        let config_path = repo()?
-            .workdir()
-            .ok_or_else(|| anyhow::anyhow!("Cannot work on bare repo"))
-            .with_code(proc_exit::sysexits::USAGE_ERR)?
-            .join("committed.toml");
-        if config_path.is_file() {
-            load_toml(&config_path).with_code(proc_exit::sysexits::CONFIG_ERR)?
-        } else {
-            config::Config::default()
+        match repo() {
+            Ok(repo) => {
+                if let Some(workdir) = repo.workdir() {
+                    let configs = layers::repo_configs(workdir, &options.work_tree)
+                        .with_code(proc_exit::sysexits::CONFIG_ERR)?;
+                    for (config_path, config) in configs {
+                        layers.push(layers::Origin::Repo(config_path), config);
+                    }
+                } else {
+                    log::debug!("Skipping repo config for bare repo");
+                }
+            }
+            Err(err) => {
+                log::debug!("Skipping repo config: {err}");
+            }
         }
-    };
-    config.update(options.to_config());
-    let config = config;
+    }
+    if let Some(env_path) = layers::env_config_path() {
+        let config = layers::load_file(&env_path).with_code(proc_exit::sysexits::CONFIG_ERR)?;
+        layers.push(layers::Origin::Env(env_path), config);
+    }
+    layers.push(layers::Origin::CommandLine, options.to_config());
+    let config = layers.merged();
 
-    let report = if options.verbose.is_silent() {
-        report::print_silent
+    let print: report::Report<'_> = if options.verbose.is_silent() {
+        &report::print_silent
     } else {
         options.format.report()
     };
+    // Only findings for commits can be recorded by `--write-baseline`
+    let unrecorded = std::cell::Cell::new(false);
+    let report = |msg: report::Message<'_>| {
+        if matches!(msg.severity, report::Severity::Error) {
+            unrecorded.set(true);
+        }
+        print(msg);
+    };
+    let report: report::Report<'_> = &report;
 
     let ignore_author_re = config
         .ignore_author_re()
@@ -210,14 +499,185 @@ fn run() -> proc_exit::ExitResult {
         .map(regex::Regex::new)
         .transpose()
         .with_code(proc_exit::sysexits::CONFIG_ERR)?;
+    let branch_name_re = config
+        .branch_name_re()
+        .map(regex::Regex::new)
+        .transpose()
+        .with_code(proc_exit::sysexits::CONFIG_ERR)?;
+    let vocabulary =
+        checks::Vocabularies::new(&config).with_code(proc_exit::sysexits::CONFIG_ERR)?;
+    let baseline = options
+        .baseline
+        .as_deref()
+        .map(baseline::Baseline::load)
+        .transpose()
+        .with_code(proc_exit::sysexits::USAGE_ERR)?;
+    let recorded = std::cell::RefCell::new(baseline::Baseline::default());
+    let baselined = std::cell::Cell::new(0);
+    let ignore_commits = std::cell::OnceCell::new();
+    let check_commit_with = |repo: &git2::Repository,
+                             commit: &git2::Commit<'_>,
+                             commit_config: &config::Config,
+                             vocabulary: &checks::Vocabularies,
+                             allowed_author_re: Option<&regex::Regex>|
+     -> Result<bool, anyhow::Error> {
+        let abbrev_id = commit.as_object().short_id().ok();
+        let source = abbrev_id
+            .as_ref()
+            .and_then(|id| id.as_str().ok())
+            .map(report::Source::from)
+            .unwrap_or_else(|| commit.id().into());
+        if ignore_commit(commit) {
+            log::trace!("Ignoring {source}");
+            return Ok(false);
+        }
+        let ignored_rules = match ignore_commits
+            .get_or_init(|| git::resolve_ignore_commits(repo, config.ignore_commits()))
+            .iter()
+            .find(|(id, _)| *id == commit.id())
+            .map(|(_, ignore)| ignore.rules())
+        {
+            Some(None) => {
+                log::trace!("Ignoring {source}");
+                return Ok(false);
+            }
+            Some(Some(rules)) => rules,
+            None => &[],
+        };
+        log::trace!("Processing {source}");
+
+        let failed = std::cell::Cell::new(false);
+        let filter = |msg: report::Message<'_>| {
+            let rule = msg.content.rule();
+            if ignored_rules.iter().any(|ignored| ignored == rule) {
+                log::debug!("{source}: ignoring `{rule}` per `ignore_commits`");
+                return;
+            }
+            if matches!(msg.severity, report::Severity::Error) {
+                if options.write_baseline.is_some() {
+                    recorded.borrow_mut().insert(commit.id(), rule);
+                }
+                if baseline
+                    .as_ref()
+                    .is_some_and(|baseline| baseline.contains(commit.id(), rule))
+                {
+                    log::debug!("{source}: `{rule}` is in the baseline");
+                    baselined.set(baselined.get() + 1);
+                    return;
+                }
+                failed.set(true);
+            }
+            print(msg);
+        };
+        checks::check_commit(
+            source,
+            commit,
+            commit_config,
+            vocabulary,
+            allowed_author_re,
+            &filter,
+        )?;
+        Ok(failed.get())
+    };
+    let check_commit = |repo: &git2::Repository, commit: &git2::Commit<'_>| {
+        check_commit_with(
+            repo,
+            commit,
+            &config,
+            &vocabulary,
+            allowed_author_re.as_ref(),
+        )
+    };
 
     let mut failed = false;
-    if let Some(output_path) = options.dump_config.as_ref() {
-        let mut defaulted_config = config::Config::from_defaults();
-        defaulted_config.update(config);
-
-        let output =
-            toml::to_string_pretty(&defaulted_config).with_code(proc_exit::Code::FAILURE)?;
+    if let Some(Command::Config(ConfigCommand::Check)) = options.command.as_ref() {
+        log::info!("Configuration is valid");
+    } else if let Some(Command::Commit(args)) = options.command.as_ref() {
+        failed |= run_commit(&repo()?, &config, &vocabulary, args, report)?;
+    } else if let Some(Command::Hook(HookCommand::PrepareCommitMsg {
+        file,
+        source,
+        commit,
+    })) = options.command.as_ref()
+    {
+        if hook::wants_template(source.as_deref()) {
+            let message = std::fs::read_to_string(file).to_sysexits()?;
+            std::fs::write(file, hook::add_template(&message, &config)).to_sysexits()?;
+        } else {
+            log::debug!(
+                "Skipping the template for a {} message {}",
+                source.as_deref().unwrap_or_default(),
+                commit.as_deref().unwrap_or_default()
+            );
+        }
+    } else if let Some(Command::Hook(hook)) = options.command.as_ref() {
+        // Unlike `repo()`, this sees the objects git quarantines until the hook accepts them
+        let repo = git2::Repository::open_from_env().with_code(proc_exit::sysexits::USAGE_ERR)?;
+        let mut text = String::new();
+        std::io::stdin().read_to_string(&mut text).to_sysexits()?;
+        let (updates, existing) = match hook {
+            HookCommand::PreReceive => (hook::parse_pre_receive(&text), "refs/*".to_owned()),
+            HookCommand::PrePush { remote, url } => {
+                log::debug!("Checking push to {remote} ({url})");
+                (
+                    hook::parse_pre_push(&text),
+                    hook::remote_tracking_glob(&repo, remote),
+                )
+            }
+            HookCommand::PrepareCommitMsg { .. } => unreachable!("handled without stdin"),
+        };
+        let updates = updates.with_code(proc_exit::sysexits::USAGE_ERR)?;
+        for update in &updates {
+            if update.is_delete() {
+                log::debug!("Skipping deletion of {}", update.name);
+                continue;
+            }
+            log::trace!("Processing {}", update.name);
+            let config = hook::ref_config(&config, &update.name);
+            let allowed_author_re = config
+                .allowed_author_re()
+                .map(regex::Regex::new)
+                .transpose()
+                .with_code(proc_exit::sysexits::CONFIG_ERR)?;
+            let vocabulary =
+                checks::Vocabularies::new(&config).with_code(proc_exit::sysexits::CONFIG_ERR)?;
+            let mut rejected = false;
+            if let Ok(tag) = repo.find_tag(update.new) {
+                let name = update
+                    .name
+                    .strip_prefix("refs/tags/")
+                    .unwrap_or(&update.name);
+                rejected |= checks::check_tag(
+                    report::Source::Tag(name),
+                    &tag,
+                    &config,
+                    &vocabulary,
+                    report,
+                )
+                .with_code(UNKNOWN_ERR)?;
+            }
+            let revspec = update.revspec(&repo, &existing).with_code(UNKNOWN_ERR)?;
+            for commit in revspec.iter().with_code(UNKNOWN_ERR)? {
+                rejected |= check_commit_with(
+                    &repo,
+                    &commit,
+                    &config,
+                    &vocabulary,
+                    allowed_author_re.as_ref(),
+                )
+                .with_code(UNKNOWN_ERR)?;
+            }
+            if rejected {
+                anstream::eprintln!("Rejecting {}, see the errors above", update.name);
+            }
+            failed |= rejected;
+        }
+    } else if let Some(output_path) = options.dump_config.as_ref() {
+        let output = match options.format {
+            Format::Json => dump::to_json(&layers, options.show_origin),
+            Format::Silent | Format::Brief => dump::to_toml(&layers, options.show_origin),
+        }
+        .with_code(proc_exit::Code::FAILURE)?;
         if output_path == std::path::Path::new("-") {
             std::io::stdout()
                 .write_all(output.as_bytes())
@@ -235,72 +695,203 @@ fn run() -> proc_exit::ExitResult {
         };
         let text = replace_comments(&text);
         let text = trim_commit_file(&text);
-        failed |= checks::check_message(path.as_path().into(), text, &config, report)
+        failed |= checks::check_message(path.as_path().into(), text, &config, &vocabulary, report)
             .with_code(UNKNOWN_ERR)?;
-    } else if let Some(commits) = options.commits.as_ref() {
+    } else if !options.commits.is_empty()
+        || options.stdin_revs
+        || options.all
+        || options.branches.is_some()
+    {
         let repo = repo()?;
-        let revspec =
-            git::RevSpec::parse(&repo, commits).with_code(proc_exit::sysexits::USAGE_ERR)?;
-        for commit in revspec.iter() {
-            let abbrev_id = commit.as_object().short_id().ok();
-            let source = abbrev_id
-                .as_ref()
-                .and_then(|id| id.as_str().ok())
-                .map(report::Source::from)
-                .unwrap_or_else(|| commit.id().into());
-            if ignore_commit(&commit) {
-                log::trace!("Ignoring {source}");
-            } else {
-                log::trace!("Processing {source}");
-                if let Some(re) = allowed_author_re.as_ref() {
-                    failed |= checks::check_allowed_author(source, &commit, re, report)
-                        .with_code(UNKNOWN_ERR)?;
-                }
-                let message = commit.message().unwrap();
-                failed |= checks::check_message(source, message, &config, report)
-                    .with_code(UNKNOWN_ERR)?;
-                if !config.merge_commit() {
-                    failed |= checks::check_merge_commit(source, &commit, report)
-                        .with_code(UNKNOWN_ERR)?;
+        let mut revspec = git::RevSpec::new(&repo);
+        for commits in &options.commits {
+            revspec
+                .push(commits)
+                .with_code(proc_exit::sysexits::USAGE_ERR)?;
+        }
+        if options.stdin_revs {
+            let mut text = String::new();
+            std::io::stdin().read_to_string(&mut text).to_sysexits()?;
+            for rev in stdin_revs(&text) {
+                revspec
+                    .push(rev)
+                    .with_code(proc_exit::sysexits::USAGE_ERR)?;
+            }
+        }
+        if options.all {
+            revspec
+                .push_all()
+                .with_code(proc_exit::sysexits::USAGE_ERR)?;
+        }
+        if let Some(glob) = options.branches.as_deref() {
+            revspec
+                .push_branches(glob)
+                .with_code(proc_exit::sysexits::USAGE_ERR)?;
+        }
+        revspec.limit(options.limits());
+        for commit in revspec.iter().with_code(UNKNOWN_ERR)? {
+            failed |= check_commit(&repo, &commit).with_code(UNKNOWN_ERR)?;
+        }
+    } else if let Some(path) = options.patch.as_deref() {
+        let paths = patch::find(path).with_code(proc_exit::sysexits::USAGE_ERR)?;
+        let mut found = false;
+        for path in &paths {
+            let text = std::fs::read_to_string(path).to_sysexits()?;
+            let patches = patch::parse(&text);
+            if patches.is_empty() {
+                // Like cover letters, which are skipped
+                log::debug!("No patches found in {}", path.display());
+            }
+            found |= !patches.is_empty();
+            for patch in &patches {
+                if let Some(re) = ignore_author_re.as_ref()
+                    && re.is_match(&patch.author)
+                {
+                    log::trace!("Ignoring patch from {}", patch.author);
+                    continue;
                 }
+                failed |= checks::check_patch(
+                    path.as_path().into(),
+                    patch,
+                    &config,
+                    &vocabulary,
+                    allowed_author_re.as_ref(),
+                    report,
+                )
+                .with_code(UNKNOWN_ERR)?;
             }
         }
+        if !found {
+            log::warn!("No patches found in {}", path.display());
+        }
+    } else if let Some(range) = options.squash.as_deref() {
+        let repo = repo()?;
+        let revspec =
+            git::RevSpec::parse(&repo, range).with_code(proc_exit::sysexits::USAGE_ERR)?;
+        let mut commits: Vec<_> = revspec
+            .iter()
+            .with_code(UNKNOWN_ERR)?
+            .filter(|commit| commit.parent_count() <= 1)
+            .collect();
+        commits.reverse();
+        let entries = commits
+            .iter()
+            .map(|commit| {
+                Ok(squash::Entry {
+                    message: commit.message()?,
+                    author: commit.author().to_string(),
+                })
+            })
+            .collect::<Result<Vec<_>, anyhow::Error>>()
+            .with_code(UNKNOWN_ERR)?;
+        let message = squash::message(options.squash_title.as_deref(), &entries);
+        if options.print_squash {
+            std::io::stdout()
+                .write_all(format!("{message}\n").as_bytes())
+                .to_sysexits()?;
+        } else {
+            failed |= checks::check_message(
+                report::Source::Range(range),
+                &message,
+                &config,
+                &vocabulary,
+                report,
+            )
+            .with_code(UNKNOWN_ERR)?;
+        }
+    } else if let Some(path) = options.pr_event.as_deref() {
+        let pr = pr::PullRequest::load(path).with_code(proc_exit::sysexits::DATA_ERR)?;
+        let message = pr.message(config.strip_pr_comments());
+        failed |= checks::check_message(
+            report::Source::PullRequest(pr.number),
+            &message,
+            &config,
+            &vocabulary,
+            report,
+        )
+        .with_code(UNKNOWN_ERR)?;
+    } else if let Some(branch) = options.branch.as_ref() {
+        let name = match branch {
+            Some(name) => name.clone(),
+            None => git::current_branch(&repo()?).with_code(proc_exit::sysexits::USAGE_ERR)?,
+        };
+        failed |= checks::check_branch_name(
+            report::Source::Branch(&name),
+            &name,
+            &config,
+            branch_name_re.as_ref(),
+            report,
+        )
+        .with_code(UNKNOWN_ERR)?;
+    } else if let Some(pattern) = options.tags.as_deref() {
+        let repo = repo()?;
+        let tags = git::annotated_tags(&repo, pattern).with_code(proc_exit::sysexits::USAGE_ERR)?;
+        if tags.is_empty() {
+            log::warn!("No annotated tags match `{pattern}`");
+        }
+        for tag in &tags {
+            let name = tag.name().with_code(UNKNOWN_ERR)?;
+            log::trace!("Processing tag {name}");
+            failed |=
+                checks::check_tag(report::Source::Tag(name), tag, &config, &vocabulary, report)
+                    .with_code(UNKNOWN_ERR)?;
+        }
+    } else if config.since_upstream() && (options.since_upstream || !grep_cli::is_readable_stdin())
+    {
+        let repo = repo()?;
+        let mut revspec =
+            git::RevSpec::since_upstream(&repo).with_code(proc_exit::sysexits::USAGE_ERR)?;
+        revspec.limit(options.limits());
+        for commit in revspec.iter().with_code(UNKNOWN_ERR)? {
+            failed |= check_commit(&repo, &commit).with_code(UNKNOWN_ERR)?;
+        }
+    } else if options.limits().walks() {
+        let repo = repo()?;
+        let mut revspec =
+            git::RevSpec::parse(&repo, "HEAD").with_code(proc_exit::sysexits::USAGE_ERR)?;
+        revspec.limit(options.limits());
+        for commit in revspec.iter().with_code(UNKNOWN_ERR)? {
+            failed |= check_commit(&repo, &commit).with_code(UNKNOWN_ERR)?;
+        }
     } else if grep_cli::is_readable_stdin() {
         let mut text = String::new();
         std::io::stdin().read_to_string(&mut text).to_sysexits()?;
         let text = trim_commit_file(&text);
-        failed |= checks::check_message(std::path::Path::new("-").into(), text, &config, report)
-            .with_code(UNKNOWN_ERR)?;
+        failed |= checks::check_message(
+            std::path::Path::new("-").into(),
+            text,
+            &config,
+            &vocabulary,
+            report,
+        )
+        .with_code(UNKNOWN_ERR)?;
     } else {
-        debug_assert_eq!(options.commits, None);
+        debug_assert!(options.commits.is_empty());
         let repo = repo()?;
         let commit = repo
             .head()
             .with_code(proc_exit::sysexits::USAGE_ERR)?
             .peel_to_commit()
             .with_code(proc_exit::sysexits::USAGE_ERR)?;
-        let abbrev_id = commit.as_object().short_id().ok();
-        let source = abbrev_id
-            .as_ref()
-            .and_then(|id| id.as_str().ok())
-            .map(report::Source::from)
-            .unwrap_or_else(|| commit.id().into());
-        if ignore_commit(&commit) {
-            log::trace!("Ignoring {source}");
-        } else {
-            log::trace!("Processing {source}");
-            if let Some(re) = allowed_author_re.as_ref() {
-                failed |= checks::check_allowed_author(source, &commit, re, report)
-                    .with_code(UNKNOWN_ERR)?;
-            }
-            let message = commit.message().unwrap();
-            failed |=
-                checks::check_message(source, message, &config, report).with_code(UNKNOWN_ERR)?;
-            if !config.merge_commit() {
-                failed |=
-                    checks::check_merge_commit(source, &commit, report).with_code(UNKNOWN_ERR)?;
-            }
+        failed |= check_commit(&repo, &commit).with_code(UNKNOWN_ERR)?;
+    }
+
+    if 0 < baselined.get() && !options.verbose.is_silent() {
+        anstream::eprintln!("Suppressed {} in the baseline", findings(baselined.get()));
+    }
+    if let Some(path) = options.write_baseline.as_deref() {
+        let recorded = recorded.into_inner();
+        recorded
+            .save(path)
+            .with_code(proc_exit::sysexits::CANT_CREAT)?;
+        if !options.verbose.is_silent() {
+            anstream::eprintln!(
+                "Recorded {} to {}",
+                findings(recorded.len()),
+                path.display()
+            );
         }
+        failed = unrecorded.get();
     }
 
     if failed {
@@ -310,6 +901,174 @@ fn run() -> proc_exit::ExitResult {
     }
 }
 
+fn findings(count: usize) -> String {
+    if count == 1 {
+        "1 finding".to_owned()
+    } else {
+        format!("{count} findings")
+    }
+}
+
+fn run_commit(
+    repo: &git2::Repository,
+    config: &config::Config,
+    vocabulary: &checks::Vocabularies,
+    args: &CommitArgs,
+    report: report::Report<'_>,
+) -> Result<bool, proc_exit::Exit> {
+    let head_tree = match repo.head() {
+        Ok(head) => Some(head.peel_to_tree().with_code(UNKNOWN_ERR)?),
+        Err(_) => None,
+    };
+    let mut index = repo.index().with_code(UNKNOWN_ERR)?;
+    let staged = repo
+        .diff_tree_to_index(head_tree.as_ref(), Some(&index), None)
+        .with_code(UNKNOWN_ERR)?
+        .deltas()
+        .filter_map(|delta| delta.new_file().path().map(|path| path.to_owned()))
+        .collect::<Vec<_>>();
+    if staged.is_empty() && !args.dry_run {
+        return Err(proc_exit::sysexits::USAGE_ERR.with_message("No changes staged to commit"));
+    }
+
+    let answers = compose::prompt(
+        &mut std::io::stdin().lock(),
+        &mut std::io::stderr(),
+        config,
+        &staged,
+    )
+    .with_code(proc_exit::sysexits::USAGE_ERR)?;
+    let message = answers.message(config);
+    let failed = checks::check_message(
+        std::path::Path::new("-").into(),
+        &message,
+        config,
+        vocabulary,
+        report,
+    )
+    .with_code(UNKNOWN_ERR)?;
+    if failed || args.dry_run {
+        // Keep the message around rather than losing what was typed
+        writeln!(std::io::stdout(), "{message}").to_sysexits()?;
+        return Ok(failed);
+    }
+
+    let signature = repo
+        .signature()
+        .with_code(proc_exit::sysexits::CONFIG_ERR)?;
+    let tree = repo
+        .find_tree(index.write_tree().with_code(UNKNOWN_ERR)?)
+        .with_code(UNKNOWN_ERR)?;
+    let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    let id = repo
+        .commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            &message,
+            &tree,
+            parent.as_slice().iter().collect::<Vec<_>>().as_slice(),
+        )
+        .with_code(UNKNOWN_ERR)?;
+    log::info!("Committed {id}");
+    Ok(false)
+}
+
+/// Install the hooks selected by `args`, or uninstall all of them without `args`
+fn run_install_hooks(
+    repo: &git2::Repository,
+    args: Option<&InstallHooksArgs>,
+) -> proc_exit::ExitResult {
+    let dir = install::hooks_dir(repo).with_code(proc_exit::sysexits::USAGE_ERR)?;
+    for hook in [
+        install::Hook::CommitMsg,
+        install::Hook::PrepareCommitMsg,
+        install::Hook::PrePush,
+    ] {
+        match args {
+            Some(args) => {
+                let selected = match hook {
+                    install::Hook::CommitMsg => true,
+                    install::Hook::PrepareCommitMsg => args.prepare_commit_msg,
+                    install::Hook::PrePush => args.pre_push,
+                };
+                if selected {
+                    install::install(&dir, hook).with_code(proc_exit::sysexits::CANT_CREAT)?;
+                }
+            }
+            None => install::uninstall(&dir, hook).with_code(proc_exit::sysexits::CANT_CREAT)?,
+        }
+    }
+    Ok(())
+}
+
+fn run_init(repo: &git2::Repository, args: &InitArgs) -> proc_exit::ExitResult {
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| anyhow::anyhow!("Cannot work on bare repo"))
+        .with_code(proc_exit::sysexits::USAGE_ERR)?;
+    let output_path = workdir.join("committed.toml");
+
+    let messages =
+        init::recent_messages(repo, args.max_count).with_code(proc_exit::sysexits::USAGE_ERR)?;
+    let history = init::History::from_messages(messages.iter().map(|m| m.as_str()));
+    if history.total() == 0 {
+        return Err(
+            proc_exit::sysexits::USAGE_ERR.with_message("No commits found to base the config on")
+        );
+    }
+    let config = history.propose();
+    let vocabulary =
+        checks::Vocabularies::new(&config).with_code(proc_exit::sysexits::CONFIG_ERR)?;
+
+    let mut passed = 0;
+    for message in &messages {
+        let failed = checks::check_message(
+            std::path::Path::new("-").into(),
+            message,
+            &config,
+            &vocabulary,
+            &report::print_silent,
+        )
+        .with_code(UNKNOWN_ERR)?;
+        if !failed {
+            passed += 1;
+        }
+    }
+    let output = init::render(&history, &config).with_code(proc_exit::Code::FAILURE)?;
+
+    std::io::stdout()
+        .write_all(output.as_bytes())
+        .to_sysexits()?;
+    log::info!(
+        "{passed} of {} recent commits pass the proposed config",
+        messages.len()
+    );
+    if args.dry_run {
+        return Ok(());
+    }
+    if output_path.exists() && !args.force {
+        return Err(proc_exit::sysexits::CANT_CREAT.with_message(format!(
+            "{} already exists, pass `--force` to overwrite it",
+            output_path.display()
+        )));
+    }
+    if !args.yes && std::io::IsTerminal::is_terminal(&std::io::stdin()) {
+        let mut stderr = std::io::stderr();
+        write!(stderr, "Write {}? [y/N] ", output_path.display()).to_sysexits()?;
+        stderr.flush().to_sysexits()?;
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer).to_sysexits()?;
+        if !matches!(answer.trim(), "y" | "Y" | "yes") {
+            return Ok(());
+        }
+    }
+    std::fs::write(&output_path, &output).to_sysexits()?;
+    log::info!("Wrote {}", output_path.display());
+
+    Ok(())
+}
+
 /// Replace all comments with blank lines until the commit trailer is found.
 fn replace_comments(text: &str) -> String {
     text.lines()
@@ -358,6 +1117,22 @@ fn trim_commit_file(message: &str) -> &str {
     captures.get(1).unwrap().as_str()
 }
 
+/// Commit ids from `--stdin-revs`, taking the first id of `rev-list --parents` or `log --oneline`
+/// lines, or only the `commit <id>` lines of full `git log` output
+fn stdin_revs(text: &str) -> Vec<&str> {
+    let full_log = text.lines().any(|line| line.starts_with("commit "));
+    text.lines()
+        .filter_map(|line| {
+            if full_log {
+                line.strip_prefix("commit ")
+            } else {
+                Some(line)
+            }
+        })
+        .filter_map(|line| line.split_whitespace().next())
+        .collect()
+}
+
 fn main() {
     human_panic::setup_panic!();
     let result = run();
@@ -374,6 +1149,27 @@ mod test {
         Options::command().debug_assert();
     }
 
+    #[test]
+    fn revs_from_stdin() {
+        let input = "1d3e6ca Add greetings
+
+89abcdef 1d3e6ca 0123456
+";
+        let actual = stdin_revs(input);
+        assert_eq!(actual, ["1d3e6ca", "89abcdef"]);
+
+        let input = "commit 0123456789abcdef0123456789abcdef01234567 (HEAD -> main)
+Author: Ed Page <eopage@gmail.com>
+Date:   Sat Jun 1 12:00:00 2024 +0000
+
+    Add greetings
+
+    commit to them
+";
+        let actual = stdin_revs(input);
+        assert_eq!(actual, ["0123456789abcdef0123456789abcdef01234567"]);
+    }
+
     #[test]
     fn empty() {
         let input = "";
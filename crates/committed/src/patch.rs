@@ -0,0 +1,300 @@
+/// A commit, as mailed by `git format-patch`
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Patch {
+    pub(crate) author: String,
+    pub(crate) message: String,
+}
+
+/// Patch files to check: `path` itself, or the `.patch` and `.mbox` files in it
+pub(crate) fn find(path: &std::path::Path) -> Result<Vec<std::path::PathBuf>, anyhow::Error> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_owned()]);
+    }
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(path)
+        .map_err(|err| anyhow::format_err!("could not read {}: {err}", path.display()))?
+    {
+        let path = entry?.path();
+        let is_patch = path
+            .extension()
+            .is_some_and(|ext| ext == "patch" || ext == "mbox");
+        if is_patch && path.is_file() {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+/// Patches in an mbox or `.patch` file, skipping cover letters
+pub(crate) fn parse(text: &str) -> Vec<Patch> {
+    let text = text.replace("\r\n", "\n");
+    split_mbox(&text)
+        .into_iter()
+        .filter_map(parse_email)
+        .collect()
+}
+
+/// Split on the `From <id> <date>` lines that start each email
+///
+/// Bodies aren't escaped, so lines like `From the docs, ...` must not start an email.
+fn split_mbox(text: &str) -> Vec<&str> {
+    let mut starts: Vec<_> = FROM_LINE_RE
+        .find_iter(text)
+        .map(|from| from.start())
+        .collect();
+    if starts.first() != Some(&0) {
+        starts.insert(0, 0);
+    }
+    starts
+        .iter()
+        .zip(starts.iter().skip(1).copied().chain([text.len()]))
+        .map(|(start, end)| &text[*start..end])
+        .filter(|email| !email.trim().is_empty())
+        .collect()
+}
+
+/// The separator `git format-patch` writes, or the mbox form `git mailsplit` accepts
+static FROM_LINE_RE: std::sync::LazyLock<regex::Regex> = std::sync::LazyLock::new(|| {
+    regex::Regex::new(
+        r"(?m)^From (?:[0-9a-f]{40} |\S+ +[A-Z][a-z]{2} [A-Z][a-z]{2} +[0-9]{1,2} [0-9]{1,2}:[0-9]{2})",
+    )
+    .unwrap()
+});
+
+fn parse_email(email: &str) -> Option<Patch> {
+    let email = if FROM_LINE_RE
+        .find(email)
+        .is_some_and(|from| from.start() == 0)
+    {
+        email
+            .split_once('\n')
+            .map(|(_, rest)| rest)
+            .unwrap_or_default()
+    } else {
+        email
+    };
+    let (headers, body) = email.split_once("\n\n").unwrap_or((email, ""));
+
+    let mut author = None;
+    let mut subject = None;
+    for header in unfold_headers(headers) {
+        let Some((key, value)) = header.split_once(':') else {
+            continue;
+        };
+        let value = decode_words(value.trim());
+        if key.eq_ignore_ascii_case("From") {
+            author = Some(value);
+        } else if key.eq_ignore_ascii_case("Subject") {
+            subject = Some(value);
+        }
+    }
+    let subject = subject?;
+    if is_cover_letter(&subject) {
+        log::debug!("Skipping cover letter `{subject}`");
+        return None;
+    }
+    let subject = strip_subject_prefix(&subject);
+
+    let body = body
+        .lines()
+        .take_while(|line| *line != "---" && !line.starts_with("diff --git "))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let body = body.trim();
+    let message = if body.is_empty() {
+        subject.to_owned()
+    } else {
+        format!("{subject}\n\n{body}")
+    };
+    Some(Patch {
+        author: author.unwrap_or_default(),
+        message,
+    })
+}
+
+/// Join header lines continued on the following indented lines
+fn unfold_headers(headers: &str) -> Vec<String> {
+    let mut unfolded: Vec<String> = Vec::new();
+    for line in headers.lines() {
+        if line.starts_with([' ', '\t'])
+            && let Some(last) = unfolded.last_mut()
+        {
+            last.push(' ');
+            last.push_str(line.trim());
+        } else {
+            unfolded.push(line.to_owned());
+        }
+    }
+    unfolded
+}
+
+/// Drop the `[PATCH v2 3/7]` style tags, as `git am` does
+fn strip_subject_prefix(subject: &str) -> &str {
+    let mut subject = subject.trim();
+    while let Some(rest) = subject.strip_prefix('[')
+        && let Some((_, rest)) = rest.split_once(']')
+    {
+        subject = rest.trim_start();
+    }
+    subject
+}
+
+fn is_cover_letter(subject: &str) -> bool {
+    static COVER_LETTER_RE: std::sync::LazyLock<regex::Regex> =
+        std::sync::LazyLock::new(|| regex::Regex::new(r"^\[[^\]]*\b0+/[0-9]+\]").unwrap());
+    COVER_LETTER_RE.is_match(subject.trim())
+}
+
+/// Decode RFC 2047 `=?UTF-8?q?...?=` and `=?UTF-8?b?...?=` words, as `git format-patch` writes
+/// non-ASCII names and subjects
+fn decode_words(value: &str) -> String {
+    static WORD_RE: std::sync::LazyLock<regex::Regex> =
+        std::sync::LazyLock::new(|| regex::Regex::new(r"=\?[^?]+\?([qQbB])\?([^?]*)\?=").unwrap());
+    let mut decoded = Vec::new();
+    let mut last = None;
+    for captures in WORD_RE.captures_iter(value) {
+        let whole = captures.get(0).unwrap();
+        let between = &value[last.unwrap_or(0)..whole.start()];
+        // Whitespace between encoded words is dropped
+        if last.is_none() || !between.trim().is_empty() {
+            decoded.extend_from_slice(between.as_bytes());
+        }
+        if captures[1].eq_ignore_ascii_case("b") {
+            decoded.extend(decode_base64(&captures[2]));
+            last = Some(whole.end());
+            continue;
+        }
+        let mut bytes = captures[2].bytes();
+        while let Some(byte) = bytes.next() {
+            match byte {
+                b'_' => decoded.push(b' '),
+                b'=' => {
+                    let hex = [bytes.next().unwrap_or(b'0'), bytes.next().unwrap_or(b'0')];
+                    let hex = std::str::from_utf8(&hex).unwrap_or("00");
+                    decoded.push(u8::from_str_radix(hex, 16).unwrap_or(b'?'));
+                }
+                byte => decoded.push(byte),
+            }
+        }
+        last = Some(whole.end());
+    }
+    decoded.extend_from_slice(&value.as_bytes()[last.unwrap_or(0)..]);
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn decode_base64(text: &str) -> Vec<u8> {
+    let mut decoded = Vec::new();
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for byte in text.bytes() {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            // Padding
+            _ => continue,
+        };
+        buffer = (buffer << 6) | u32::from(value);
+        bits += 6;
+        if 8 <= bits {
+            bits -= 8;
+            decoded.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    decoded
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const PATCH: &str = "From 0123456789abcdef0123456789abcdef01234567 Mon Sep 17 00:00:00 2001
+From: =?UTF-8?q?J=C3=BCrgen=20Smith?= <jurgen@example.com>
+Date: Sat, 1 Jun 2024 12:00:00 +0000
+Subject: [PATCH v2 3/7] Add greetings to
+ the parser
+
+The parser now says hello.
+
+Signed-off-by: Jürgen Smith <jurgen@example.com>
+---
+ src/parser.rs | 2 ++
+ 1 file changed, 2 insertions(+)
+
+diff --git a/src/parser.rs b/src/parser.rs
+--
+2.45.0
+";
+
+    #[test]
+    fn parse_patch() {
+        assert_eq!(
+            parse(PATCH),
+            [Patch {
+                author: "Jürgen Smith <jurgen@example.com>".to_owned(),
+                message: "Add greetings to the parser
+
+The parser now says hello.
+
+Signed-off-by: Jürgen Smith <jurgen@example.com>"
+                    .to_owned(),
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_mbox() {
+        let cover = "From 0000000000000000000000000000000000000000 Mon Sep 17 00:00:00 2001
+From: Ed <ed@example.com>
+Subject: [PATCH 0/7] Greetings
+
+*** BLURB HERE ***
+";
+        let mbox = format!("{cover}\n{PATCH}\n{PATCH}");
+        assert_eq!(parse(&mbox).len(), 2);
+
+        let quoting = "From 0123456789abcdef0123456789abcdef01234567 Mon Sep 17 00:00:00 2001
+From: Ed <ed@example.com>
+Subject: [PATCH] Quote the docs
+
+From the docs, greetings are the first thing said.
+This line is long enough that it should still be checked by the line length rule.
+---
+";
+        let mbox = format!("{quoting}\nFrom ed@example.com Sat Jun  1 12:00:00 2024\n{PATCH}");
+        let patches = parse(&mbox);
+        assert_eq!(patches.len(), 2);
+        assert!(
+            patches[0]
+                .message
+                .ends_with("checked by the line length rule.")
+        );
+    }
+
+    #[test]
+    fn encoded_words() {
+        assert_eq!(
+            decode_words("=?UTF-8?q?J=C3=BCrgen?= =?UTF-8?q?_Smith?= <j@example.com>"),
+            "Jürgen Smith <j@example.com>"
+        );
+        assert_eq!(
+            decode_words("=?UTF-8?b?R3LDvMOfZQ==?= an alle"),
+            "Grüße an alle"
+        );
+        assert_eq!(
+            decode_words("Ed Page <ed@example.com>"),
+            "Ed Page <ed@example.com>"
+        );
+    }
+
+    #[test]
+    fn subject_prefix() {
+        assert_eq!(strip_subject_prefix("[PATCH v2 3/7] Add"), "Add");
+        assert_eq!(strip_subject_prefix("[RFC] [PATCH] Add"), "Add");
+        assert_eq!(strip_subject_prefix("Add [ci skip]"), "Add [ci skip]");
+    }
+}
@@ -0,0 +1,146 @@
+/// Pull or merge request, as read from a CI event payload
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct PullRequest {
+    pub(crate) number: u64,
+    pub(crate) title: String,
+    pub(crate) body: String,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum Event {
+    PullRequestWebhook {
+        pull_request: GitHubPullRequest,
+    },
+    MergeRequestWebhook {
+        object_attributes: GitLabMergeRequest,
+    },
+    PullRequest(GitHubPullRequest),
+    MergeRequest(GitLabMergeRequest),
+}
+
+#[derive(serde::Deserialize)]
+struct GitHubPullRequest {
+    number: u64,
+    title: String,
+    body: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+struct GitLabMergeRequest {
+    iid: u64,
+    title: String,
+    description: Option<String>,
+}
+
+impl PullRequest {
+    /// Read a GitHub `pull_request` event or GitLab merge request event
+    ///
+    /// The bare pull or merge request objects from their REST APIs are accepted as well.
+    pub(crate) fn load(path: &std::path::Path) -> Result<Self, anyhow::Error> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|err| anyhow::format_err!("could not read {}: {err}", path.display()))?;
+        Self::parse(&text).map_err(|err| anyhow::format_err!("invalid {}: {err}", path.display()))
+    }
+
+    fn parse(text: &str) -> Result<Self, anyhow::Error> {
+        let event: serde_json::Value = serde_json::from_str(text)?;
+        let event = serde_json::from_value(event).map_err(|_| {
+            anyhow::format_err!("expected a GitHub `pull_request` or GitLab `merge_request` event")
+        })?;
+        let (number, title, body) = match event {
+            Event::PullRequestWebhook {
+                pull_request:
+                    GitHubPullRequest {
+                        number,
+                        title,
+                        body,
+                    },
+            }
+            | Event::PullRequest(GitHubPullRequest {
+                number,
+                title,
+                body,
+            }) => (number, title, body),
+            Event::MergeRequestWebhook {
+                object_attributes:
+                    GitLabMergeRequest {
+                        iid,
+                        title,
+                        description,
+                    },
+            }
+            | Event::MergeRequest(GitLabMergeRequest {
+                iid,
+                title,
+                description,
+            }) => (iid, title, description),
+        };
+        Ok(Self {
+            number,
+            title,
+            body: body.unwrap_or_default().replace("\r\n", "\n"),
+        })
+    }
+
+    /// The commit message this would become when squash-merged
+    pub(crate) fn message(&self, strip_comments: bool) -> String {
+        let body = if strip_comments {
+            strip_html_comments(&self.body)
+        } else {
+            std::borrow::Cow::Borrowed(self.body.as_str())
+        };
+        let body = body.trim();
+        if body.is_empty() {
+            self.title.trim().to_owned()
+        } else {
+            format!("{}\n\n{body}", self.title.trim())
+        }
+    }
+}
+
+/// Drop `<!-- ... -->`, like the instructions in pull request templates
+fn strip_html_comments(body: &str) -> std::borrow::Cow<'_, str> {
+    static COMMENT_RE: std::sync::LazyLock<regex::Regex> =
+        std::sync::LazyLock::new(|| regex::Regex::new(r"(?s)<!--.*?-->\n?").unwrap());
+    COMMENT_RE.replace_all(body, "")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_github() {
+        let pr = PullRequest::parse(
+            r#"{"action": "opened", "pull_request": {"number": 7, "title": "Add greetings", "body": "Say hello\r\n"}}"#,
+        )
+        .unwrap();
+        assert_eq!(pr.number, 7);
+        assert_eq!(pr.message(true), "Add greetings\n\nSay hello");
+    }
+
+    #[test]
+    fn parse_gitlab() {
+        let pr = PullRequest::parse(
+            r#"{"object_kind": "merge_request", "object_attributes": {"iid": 3, "title": "Add greetings", "description": null}}"#,
+        )
+        .unwrap();
+        assert_eq!(pr.number, 3);
+        assert_eq!(pr.message(true), "Add greetings");
+    }
+
+    #[test]
+    fn strip_comments() {
+        let pr = PullRequest {
+            number: 1,
+            title: "Add greetings".to_owned(),
+            body: "<!-- Describe the change -->\nSay hello\n<!--\nLink issues\n-->\n".to_owned(),
+        };
+        assert_eq!(pr.message(true), "Add greetings\n\nSay hello");
+        assert_eq!(
+            pr.message(false),
+            "Add greetings\n\n<!-- Describe the change -->\nSay hello\n<!--\nLink issues\n-->"
+        );
+    }
+}
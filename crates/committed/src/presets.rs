@@ -0,0 +1,37 @@
+/// Prefix for referencing a built-in preset from `extends`
+pub(crate) const PRESET_PREFIX: &str = "preset:";
+
+static PRESETS: &[(&str, &str)] = &[
+    ("seven-rules", include_str!("presets/seven-rules.toml")),
+    (
+        "conventional-strict",
+        include_str!("presets/conventional-strict.toml"),
+    ),
+    ("angular", include_str!("presets/angular.toml")),
+    ("linux-kernel", include_str!("presets/linux-kernel.toml")),
+    ("lenient", include_str!("presets/lenient.toml")),
+];
+
+pub(crate) fn find(name: &str) -> Option<&'static str> {
+    PRESETS
+        .iter()
+        .find(|(preset, _)| *preset == name)
+        .map(|(_, text)| *text)
+}
+
+pub(crate) fn names() -> impl Iterator<Item = &'static str> {
+    PRESETS.iter().map(|(name, _)| *name)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn presets_parse() {
+        for (name, text) in PRESETS {
+            let config: Result<crate::config::Config, _> = toml::from_str(text);
+            assert!(config.is_ok(), "preset `{name}` is invalid: {config:?}");
+        }
+    }
+}
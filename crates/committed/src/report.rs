@@ -21,6 +21,18 @@ impl<'s> Message<'s> {
             content: content.into(),
         }
     }
+
+    pub(crate) fn warning<S, C>(source: S, content: C) -> Self
+    where
+        S: Into<Source<'s>>,
+        C: Into<Content<'s>>,
+    {
+        Message {
+            source: source.into(),
+            severity: Severity::Warning,
+            content: content.into(),
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, serde::Serialize, derive_more::From, derive_more::Display)]
@@ -33,6 +45,15 @@ pub(crate) enum Source<'s> {
     ShortId(&'s str),
     #[display("{}", _0.display())]
     Path(&'s std::path::Path),
+    #[from(skip)]
+    Tag(&'s str),
+    #[from(skip)]
+    Branch(&'s str),
+    #[from(skip)]
+    #[display("#{}", _0)]
+    PullRequest(u64),
+    #[from(skip)]
+    Range(&'s str),
 }
 
 fn serialize_oid<S>(oid: &git2::Oid, s: S) -> Result<S::Ok, S::Error>
@@ -49,6 +70,8 @@ where
 pub(crate) enum Severity {
     #[display("error")]
     Error,
+    #[display("warning")]
+    Warning,
 }
 
 #[derive(Debug, serde::Serialize, derive_more::From, derive_more::Display)]
@@ -69,6 +92,140 @@ pub(crate) enum Content<'s> {
     DisallowedCommitScope(DisallowedCommitScope),
     MergeCommitDisallowed(MergeCommitDisallowed),
     DisallowedAuthor(DisallowedAuthor<'s>),
+    UnusedSuppression(UnusedSuppression),
+    UnknownSuppression(UnknownSuppression),
+    MissingTagVersion(MissingTagVersion),
+    InvalidBranchName(InvalidBranchName<'s>),
+}
+
+impl Content<'_> {
+    /// Name for referring to the rule that produced this content, like in suppressions
+    pub(crate) fn rule(&self) -> &'static str {
+        match self {
+            Content::EmptyCommit(_) => "empty",
+            Content::SubjectTooLong(_) => "subject-length",
+            Content::LineTooLong(_) => "line-length",
+            Content::CapitalizeSubject(_) => "subject-capitalized",
+            Content::NoPunctuation(_) => "subject-not-punctuated",
+            Content::Imperative(_) => "imperative",
+            Content::Wip(_) => "wip",
+            Content::Fixup(_) => "fixup",
+            Content::InvalidCommitFormat(_) => "style",
+            Content::DisallowedCommitType(_) => "allowed-types",
+            Content::DisallowedCommitScope(_) => "allowed-scopes",
+            Content::MergeCommitDisallowed(_) => "merge-commit",
+            Content::DisallowedAuthor(_) => "allowed-author",
+            Content::UnusedSuppression(_) | Content::UnknownSuppression(_) => "unused-suppression",
+            Content::MissingTagVersion(_) => "tag-version",
+            Content::InvalidBranchName(_) => "branch-name",
+        }
+    }
+}
+
+/// Every name returned by [`Content::rule`], in the order of its variants
+pub(crate) const RULES: [&str; 16] = [
+    "empty",
+    "subject-length",
+    "line-length",
+    "subject-capitalized",
+    "subject-not-punctuated",
+    "imperative",
+    "wip",
+    "fixup",
+    "style",
+    "allowed-types",
+    "allowed-scopes",
+    "merge-commit",
+    "allowed-author",
+    "unused-suppression",
+    "tag-version",
+    "branch-name",
+];
+
+#[test]
+fn rules_match_content() {
+    let contents: Vec<Content<'_>> = vec![
+        EmptyCommit {}.into(),
+        SubjectTooLong {
+            max_length: 0,
+            actual_length: 0,
+        }
+        .into(),
+        LineTooLong {
+            max_length: 0,
+            actual_length: 0,
+        }
+        .into(),
+        CapitalizeSubject { first_word: "" }.into(),
+        NoPunctuation { punctuation: '.' }.into(),
+        Imperative { first_word: "" }.into(),
+        Wip {}.into(),
+        Fixup {}.into(),
+        InvalidCommitFormat {
+            error: anyhow::anyhow!(""),
+            style: crate::config::Style::None,
+        }
+        .into(),
+        DisallowedCommitType {
+            used: String::new(),
+            allowed: Vec::new(),
+        }
+        .into(),
+        DisallowedCommitScope {
+            used: String::new(),
+            allowed: Vec::new(),
+        }
+        .into(),
+        MergeCommitDisallowed {}.into(),
+        DisallowedAuthor {
+            used: String::new(),
+            allowed: "",
+        }
+        .into(),
+        UnusedSuppression {
+            rule: String::new(),
+        }
+        .into(),
+        UnknownSuppression {
+            rule: String::new(),
+            suggestion: None,
+        }
+        .into(),
+        MissingTagVersion {
+            version: String::new(),
+        }
+        .into(),
+        InvalidBranchName {
+            name: "",
+            expected: String::new(),
+        }
+        .into(),
+    ];
+    // Fails to compile when a variant is added, as a reminder to add it above
+    for content in &contents {
+        match content {
+            Content::EmptyCommit(_)
+            | Content::SubjectTooLong(_)
+            | Content::LineTooLong(_)
+            | Content::CapitalizeSubject(_)
+            | Content::NoPunctuation(_)
+            | Content::Imperative(_)
+            | Content::Wip(_)
+            | Content::Fixup(_)
+            | Content::InvalidCommitFormat(_)
+            | Content::DisallowedCommitType(_)
+            | Content::DisallowedCommitScope(_)
+            | Content::MergeCommitDisallowed(_)
+            | Content::DisallowedAuthor(_)
+            | Content::UnusedSuppression(_)
+            | Content::UnknownSuppression(_)
+            | Content::MissingTagVersion(_)
+            | Content::InvalidBranchName(_) => {}
+        }
+    }
+    let mut rules: Vec<_> = contents.iter().map(|content| content.rule()).collect();
+    rules.dedup();
+    assert_eq!(rules, RULES);
 }
 
 #[derive(Clone, Debug, serde::Serialize)]
@@ -194,7 +351,49 @@ pub(crate) struct DisallowedAuthor<'s> {
     pub(crate) allowed: &'s str,
 }
 
-pub(crate) type Report = fn(msg: Message<'_>);
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+#[derive(derive_more::Display)]
+#[display("Suppression of `{}` is unused", rule)]
+pub(crate) struct UnusedSuppression {
+    pub(crate) rule: String,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) struct UnknownSuppression {
+    pub(crate) rule: String,
+    pub(crate) suggestion: Option<&'static str>,
+}
+
+impl std::fmt::Display for UnknownSuppression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Suppression of unknown rule `{}`", self.rule)?;
+        if let Some(suggestion) = self.suggestion {
+            write!(f, ", did you mean `{suggestion}`?")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+#[derive(derive_more::Display)]
+#[display("Subject should mention the version `{}`", version)]
+pub(crate) struct MissingTagVersion {
+    pub(crate) version: String,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+#[derive(derive_more::Display)]
+#[display("Invalid branch name `{}`, expected {}", name, expected)]
+pub(crate) struct InvalidBranchName<'s> {
+    pub(crate) name: &'s str,
+    pub(crate) expected: String,
+}
+
+pub(crate) type Report<'r> = &'r dyn Fn(Message<'_>);
 
 pub(crate) fn print_silent(_: Message<'_>) {}
 
@@ -202,6 +401,7 @@ pub(crate) fn print_brief(msg: Message<'_>) {
     let palette = crate::color::Palette::new();
     let severity_style = match msg.severity {
         Severity::Error => palette.error,
+        Severity::Warning => palette.warning,
     };
     println!(
         "{:#}: {:#} {:#}",
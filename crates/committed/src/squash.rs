@@ -0,0 +1,121 @@
+const CO_AUTHOR_KEY: &str = "Co-authored-by";
+
+/// A commit to be squashed
+#[derive(Debug)]
+pub(crate) struct Entry<'c> {
+    pub(crate) message: &'c str,
+    pub(crate) author: String,
+}
+
+/// Message for squashing `commits`, oldest first, like GitHub's squash-merge
+///
+/// The subject is `title` or the first commit's subject, followed by a bulleted list of the commit
+/// subjects and the co-authors of all the commits.  A single commit keeps its body instead.
+pub(crate) fn message(title: Option<&str>, commits: &[Entry<'_>]) -> String {
+    let subject = |message: &str| message.lines().next().unwrap_or_default().trim().to_owned();
+    let title = title
+        .map(|title| title.trim().to_owned())
+        .or_else(|| commits.first().map(|commit| subject(commit.message)))
+        .unwrap_or_default();
+
+    if let [commit] = commits {
+        let body = commit
+            .message
+            .split_once('\n')
+            .map(|(_, body)| body.trim())
+            .unwrap_or_default();
+        return if body.is_empty() {
+            title
+        } else {
+            format!("{title}\n\n{body}")
+        };
+    }
+
+    let mut co_authors: Vec<String> = Vec::new();
+    let mut add_co_author = |co_author: &str| {
+        if !co_authors
+            .iter()
+            .any(|existing| existing.eq_ignore_ascii_case(co_author))
+        {
+            co_authors.push(co_author.to_owned());
+        }
+    };
+    let squash_author = commits.first().map(|commit| commit.author.as_str());
+    for commit in commits {
+        if Some(commit.author.as_str()) != squash_author {
+            add_co_author(&commit.author);
+        }
+        for line in commit.message.lines() {
+            if let Some((key, value)) = line.split_once(':')
+                && key.trim().eq_ignore_ascii_case(CO_AUTHOR_KEY)
+            {
+                add_co_author(value.trim());
+            }
+        }
+    }
+
+    let mut message = title;
+    if !commits.is_empty() {
+        message.push_str("\n\n");
+        let subjects: Vec<_> = commits
+            .iter()
+            .map(|commit| format!("* {}", subject(commit.message)))
+            .collect();
+        message.push_str(&subjects.join("\n"));
+    }
+    if !co_authors.is_empty() {
+        message.push_str("\n\n");
+        let trailers: Vec<_> = co_authors
+            .iter()
+            .map(|co_author| format!("{CO_AUTHOR_KEY}: {co_author}"))
+            .collect();
+        message.push_str(&trailers.join("\n"));
+    }
+    message
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn squash_commits() {
+        let commits = [
+            Entry {
+                message: "Add greetings\n\nSay hello",
+                author: "Ed Page <ed@example.com>".to_owned(),
+            },
+            Entry {
+                message: "Fix typo\n\nCo-authored-by: Alex <alex@example.com>",
+                author: "Sam <sam@example.com>".to_owned(),
+            },
+            Entry {
+                message: "Wave goodbye\n\nco-authored-by: alex <ALEX@example.com>",
+                author: "Ed Page <ed@example.com>".to_owned(),
+            },
+        ];
+        assert_eq!(
+            message(None, &commits),
+            "Add greetings
+
+* Add greetings
+* Fix typo
+* Wave goodbye
+
+Co-authored-by: Sam <sam@example.com>
+Co-authored-by: Alex <alex@example.com>"
+        );
+        assert!(
+            message(Some("Greet people (#12)"), &commits).starts_with("Greet people (#12)\n\n")
+        );
+    }
+
+    #[test]
+    fn squash_single_commit() {
+        let commits = [Entry {
+            message: "Add greetings\n\nSay hello",
+            author: "Ed Page <ed@example.com>".to_owned(),
+        }];
+        assert_eq!(message(None, &commits), "Add greetings\n\nSay hello");
+    }
+}
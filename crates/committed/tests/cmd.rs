@@ -88,14 +88,128 @@ fn fixup_multiple() {
         .stderr_eq(str![]);
 }
 
+#[test]
+fn imperative_deny() {
+    run_committed("Bump version", "imperative_deny = [\"bump\"]")
+        .code(1)
+        .stdout_eq(str![[r#"
+-: error Subject should be in the imperative mood but found `Bump`
+
+"#]])
+        .stderr_eq(str![]);
+}
+
+#[test]
+fn imperative_prefix() {
+    run_committed(
+        "[parser] added greetings",
+        r#"
+subject_capitalized = false
+imperative_prefix_re = '^\[[^\]]*\]'
+"#,
+    )
+    .code(1)
+    .stdout_eq(str![[r#"
+-: error Subject should be in the imperative mood but found `added`
+
+"#]])
+    .stderr_eq(str![]);
+}
+
+#[test]
+fn suppression_skips_rule() {
+    run_committed(
+        "Release the first stable version of the parser and its greetings
+
+Committed-Ignore: subject-length",
+        "",
+    )
+    .code(0)
+    .stdout_eq(str![])
+    .stderr_eq(str![]);
+}
+
+#[test]
+fn suppression_unused() {
+    run_committed(
+        "Release the parser
+
+Committed-Ignore: subject-length, wip",
+        "",
+    )
+    .code(0)
+    .stdout_eq(str![[r#"
+-: warning Suppression of `subject-length` is unused
+-: warning Suppression of `wip` is unused
+
+"#]])
+    .stderr_eq(str![]);
+}
+
+#[test]
+fn suppression_unknown() {
+    run_committed(
+        "Release the parser
+
+Committed-Ignore: subject-lenght, bogus",
+        "",
+    )
+    .code(0)
+    .stdout_eq(str![[r#"
+-: warning Suppression of unknown rule `subject-lenght`, did you mean `subject-length`?
+-: warning Suppression of unknown rule `bogus`
+
+"#]])
+    .stderr_eq(str![]);
+}
+
+#[test]
+fn suppression_disallowed() {
+    run_committed(
+        "Release the first stable version of the parser and its greetings
+
+Committed-Ignore: subject-length",
+        "allow_suppressions = false",
+    )
+    .code(1)
+    .stdout_eq(str![[r#"
+-: error Commit subject is too long, 64 exceeds the max length of 50
+
+"#]])
+    .stderr_eq(str![]);
+}
+
+#[test]
+fn suppression_policy() {
+    run_committed_repo(
+        "Add greetings
+
+Committed-Ignore: allowed-author",
+        r#"allowed_author_re = "@example.invalid>$""#,
+    )
+    .code(1)
+    .stdout_eq(str![[r#"
+[..]: error Disallowed author `[..]` used, please use one matching `@example.invalid>$`
+
+"#]])
+    .stderr_eq(str![]);
+}
+
 #[track_caller]
+/// `committed`, without the user's config or `$COMMITTED_CONFIG`
+fn committed() -> snapbox::cmd::Command {
+    snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("committed"))
+        .env("XDG_CONFIG_HOME", env!("CARGO_TARGET_TMPDIR"))
+        .env_remove("COMMITTED_CONFIG")
+}
+
 fn run_committed(message: &str, config: &str) -> snapbox::cmd::OutputAssert {
     let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
     let root_dir = root.path().unwrap();
     let config_path = root_dir.join("committed.toml");
     std::fs::write(&config_path, config).unwrap();
 
-    let assert = snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("committed"))
+    let assert = committed()
         .arg("--commit-file=-")
         .arg("--config")
         .arg(&config_path)
@@ -108,6 +222,553 @@ fn run_committed(message: &str, config: &str) -> snapbox::cmd::OutputAssert {
     assert
 }
 
+#[test]
+fn dump_config_show_origin() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_dir = root.path().unwrap();
+    let config_path = root_dir.join("committed.toml");
+    std::fs::write(&config_path, "subject_length = 60").unwrap();
+    let env_path = root_dir.join("env.toml");
+    std::fs::write(&env_path, "line_length = 100").unwrap();
+
+    committed()
+        .arg("--dump-config=-")
+        .arg("--show-origin")
+        .arg("--config")
+        .arg(&config_path)
+        .arg("--no-wip")
+        .env("XDG_CONFIG_HOME", root_dir)
+        .env("COMMITTED_CONFIG", &env_path)
+        .current_dir(root_dir)
+        .assert()
+        .success()
+        .stdout_eq(str![[r##"
+# Presets (`preset:<name>`) or config files to build on, in order
+# Default: (none)
+# Source: default
+# extends = []
+
+# Authors to ignore the commits for. Generally used with bots out of your control.
+# Default: (none)
+# Source: default
+# ignore_author_re = "<regex>"
+
+# Commits to ignore, by full or abbreviated id, optionally for only some rules
+# Default: []
+# Source: default
+ignore_commits = []
+
+# Number of columns the subject can occupy
+# Default: 50
+# Source: `--config` [..]/committed.toml
+subject_length = 60
+
+# Whether the subject is required to be capitalized
+# Default: true
+# Source: default
+subject_capitalized = true
+
+# Prevent the subject from ending in punctuation
+# Default: true
+# Source: default
+subject_not_punctuated = true
+
+# Require the subject to start with an imperative verb
+# Default: true
+# Source: default
+imperative_subject = true
+
+# Words to always accept as imperative verbs
+# Default: []
+# Source: default
+imperative_allow = []
+
+# Words to always reject as imperative verbs
+# Default: []
+# Source: default
+imperative_deny = []
+
+# Leading text to skip when looking for the imperative verb, like ticket IDs
+# Default: (none)
+# Source: default
+# imperative_prefix_re = "<regex>"
+
+# Disallow fixup commits
+# Default: true
+# Overridden by: --no-fixup
+# Source: default
+no_fixup = true
+
+# Disallow WIP commits
+# Default: true
+# Overridden by: --no-wip
+# Source: command-line
+no_wip = true
+
+# Max number of columns any line can occupy, with 0 for no limit
+# Default: 0
+# Source: default
+hard_line_length = 0
+
+# Number of columns any line with a break can occupy, including subject, with 0 for no limit
+# Default: 72
+# Source: `$COMMITTED_CONFIG` [..]/env.toml
+line_length = 100
+
+# Commit style convention
+# Allowed values: "conventional", "none"
+# Default: "none"
+# Source: default
+style = "none"
+
+# (Conventional) Accepted commit types
+# Default: ["fix", "feat", "chore", "docs", "style", "refactor", "perf", "test"]
+# Source: default
+allowed_types = ["fix", "feat", "chore", "docs", "style", "refactor", "perf", "test"]
+
+# (Conventional) Accepted commit scopes, with an empty list allowing all scopes
+# Default: []
+# Source: default
+allowed_scopes = []
+
+# Without commits specified, check those since `HEAD` branched from its upstream, rather than `HEAD`
+# Default: false
+# Overridden by: --since-upstream
+# Source: default
+since_upstream = false
+
+# Ignore `<!-- ... -->` comments, like pull request template instructions, with `--pr-event`
+# Default: true
+# Source: default
+strip_pr_comments = true
+
+# Require branch names to match this regular expression
+# Default: (none)
+# Source: default
+# branch_name_re = "<regex>"
+
+# Require branch names to start with one of `allowed_types`, like `feat/`
+# Default: false
+# Source: default
+branch_type_prefix = false
+
+# Allow merge commits
+# Default: true
+# Overridden by: --no-merge-commit
+# Source: default
+merge_commit = true
+
+# Rules to use instead for merge commits, like `style = "none"` for `Merge pull request #...`
+# Default: (none)
+# Source: default
+# merge_rules = {}
+
+# Rules to use instead for annotated tag messages
+# Default: (none)
+# Source: default
+# tag_rules = {}
+
+# (Tags) Require the subject to mention the tag's version, like `1.2.0` for `v1.2.0`
+# Default: false
+# Source: default
+tag_subject_version = false
+
+# Rules to use instead for pushes to refs matching the glob, like `refs/heads/main`, with
+# `committed hook pre-receive` or `pre-push`
+# Default: (none)
+# Source: default
+# ref_rules = {}
+
+# Allow skipping rules for a commit with a trailer like `Committed-Ignore: subject-length`
+# Default: true
+# Source: default
+allow_suppressions = true
+
+# Trailer for listing rules to skip for a commit
+# Default: "Committed-Ignore"
+# Source: default
+suppression_trailer = "Committed-Ignore"
+
+# Require commit author to match this regular expression
+# Default: (none)
+# Source: default
+# allowed_author_re = "<regex>"
+
+"##]])
+        .stderr_eq(str![]);
+
+    root.close().unwrap();
+}
+
+#[test]
+fn dump_config_json_show_origin() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_dir = root.path().unwrap();
+    let config_path = root_dir.join("committed.toml");
+    std::fs::write(&config_path, "subject_length = 60").unwrap();
+    let env_path = root_dir.join("env.toml");
+    std::fs::write(&env_path, "line_length = 100").unwrap();
+
+    committed()
+        .arg("--dump-config=-")
+        .arg("--show-origin")
+        .arg("--format=json")
+        .arg("--config")
+        .arg(&config_path)
+        .arg("--no-wip")
+        .env("XDG_CONFIG_HOME", root_dir)
+        .env("COMMITTED_CONFIG", &env_path)
+        .current_dir(root_dir)
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+{
+  "ignore_commits": {
+    "value": [],
+    "origin": "default"
+  },
+  "subject_length": {
+    "value": 60,
+    "origin": "`--config` [..]/committed.toml"
+  },
+  "subject_capitalized": {
+    "value": true,
+    "origin": "default"
+  },
+  "subject_not_punctuated": {
+    "value": true,
+    "origin": "default"
+  },
+  "imperative_subject": {
+    "value": true,
+    "origin": "default"
+  },
+  "imperative_allow": {
+    "value": [],
+    "origin": "default"
+  },
+  "imperative_deny": {
+    "value": [],
+    "origin": "default"
+  },
+  "no_fixup": {
+    "value": true,
+    "origin": "default"
+  },
+  "no_wip": {
+    "value": true,
+    "origin": "command-line"
+  },
+  "hard_line_length": {
+    "value": 0,
+    "origin": "default"
+  },
+  "line_length": {
+    "value": 100,
+    "origin": "`$COMMITTED_CONFIG` [..]/env.toml"
+  },
+  "style": {
+    "value": "none",
+    "origin": "default"
+  },
+  "allowed_types": {
+    "value": [
+      "fix",
+      "feat",
+      "chore",
+      "docs",
+      "style",
+      "refactor",
+      "perf",
+      "test"
+    ],
+    "origin": "default"
+  },
+  "allowed_scopes": {
+    "value": [],
+    "origin": "default"
+  },
+  "since_upstream": {
+    "value": false,
+    "origin": "default"
+  },
+  "strip_pr_comments": {
+    "value": true,
+    "origin": "default"
+  },
+  "branch_type_prefix": {
+    "value": false,
+    "origin": "default"
+  },
+  "merge_commit": {
+    "value": true,
+    "origin": "default"
+  },
+  "tag_subject_version": {
+    "value": false,
+    "origin": "default"
+  },
+  "allow_suppressions": {
+    "value": true,
+    "origin": "default"
+  },
+  "suppression_trailer": {
+    "value": "Committed-Ignore",
+    "origin": "default"
+  }
+}
+
+"#]])
+        .stderr_eq(str![]);
+
+    root.close().unwrap();
+}
+
+#[test]
+fn dump_config_nested_rules() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_dir = root.path().unwrap();
+    let config_path = root_dir.join("committed.toml");
+    std::fs::write(
+        &config_path,
+        r#"
+[merge_rules]
+style = "none"
+
+[tag_rules]
+subject_length = 0
+
+[ref_rules."refs/heads/main"]
+subject_capitalized = false
+"#,
+    )
+    .unwrap();
+
+    committed()
+        .arg("--dump-config=-")
+        .arg("--config")
+        .arg(&config_path)
+        .current_dir(root_dir)
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+...
+merge_rules = { style = "none" }
+...
+tag_rules = { subject_length = 0 }
+...
+ref_rules = { "refs/heads/main" = { subject_capitalized = false } }
+...
+"#]])
+        .stderr_eq(str![]);
+
+    root.close().unwrap();
+}
+#[test]
+fn extends_preset_and_file() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_dir = root.path().unwrap();
+    std::fs::create_dir_all(root_dir.join("shared")).unwrap();
+    std::fs::write(
+        root_dir.join("shared/committed.toml"),
+        "subject_length = 10",
+    )
+    .unwrap();
+    let config_path = root_dir.join("committed.toml");
+    std::fs::write(
+        &config_path,
+        r#"extends = ["preset:lenient", "shared/committed.toml"]"#,
+    )
+    .unwrap();
+
+    committed()
+        .arg("--commit-file=-")
+        .arg("--config")
+        .arg(&config_path)
+        .current_dir(root_dir)
+        .stdin("lowercase subject that is long.")
+        .assert()
+        .code(1)
+        .stdout_eq(str![[r#"
+-: error Commit subject is too long, 31 exceeds the max length of 10
+
+"#]])
+        .stderr_eq(str![]);
+
+    root.close().unwrap();
+}
+
+#[test]
+fn extends_cycle() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_dir = root.path().unwrap();
+    std::fs::write(root_dir.join("a.toml"), r#"extends = ["b.toml"]"#).unwrap();
+    std::fs::write(root_dir.join("b.toml"), r#"extends = ["a.toml"]"#).unwrap();
+
+    committed()
+        .arg("--commit-file=-")
+        .arg("--config=a.toml")
+        .current_dir(root_dir)
+        .stdin("Add greetings")
+        .assert()
+        .code(78)
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+[..]/b.toml: `extends` forms a cycle: [..]/a.toml -> [..]/b.toml -> [..]/a.toml
+
+"#]]);
+
+    root.close().unwrap();
+}
+
+#[test]
+fn config_check_unknown_field() {
+    run_config_check("subject_lenght = 60")
+        .code(78)
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+invalid config in committed.toml: TOML parse error at line 1, column 1
+  |
+1 | subject_lenght = 60
+  | ^^^^^^^^^^^^^^
+unknown field `subject_lenght`, expected one of `$schema`, `extends`, `ignore_author_re`, `ignore_commits`, `subject_length`, `subject_capitalized`, `subject_not_punctuated`, `imperative_subject`, `imperative_allow`, `imperative_deny`, `imperative_prefix_re`, `no_fixup`, `no_wip`, `hard_line_length`, `line_length`, `style`, `allowed_types`, `allowed_scopes`, `since_upstream`, `strip_pr_comments`, `branch_name_re`, `branch_type_prefix`, `merge_commit`, `merge_rules`, `tag_rules`, `tag_subject_version`, `ref_rules`, `allow_suppressions`, `suppression_trailer`, `allowed_author_re`
+help: did you mean `subject_length`?
+
+"#]]);
+}
+
+#[test]
+fn config_check_invalid_regex() {
+    run_config_check(r#"ignore_author_re = "(dependabot""#)
+        .code(78)
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+invalid config in committed.toml: TOML parse error at line 1, column 20
+  |
+1 | ignore_author_re = "(dependabot"
+  |                    ^^^^^^^^^^^^^
+regex parse error:
+    (dependabot
+    ^
+error: unclosed group
+
+"#]]);
+}
+
+#[test]
+fn config_check_unknown_ignored_rule() {
+    run_config_check(r#"ignore_commits = [{ commit = "abc1234", rules = ["subject-lenght"] }]"#)
+        .code(78)
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+invalid config in committed.toml: TOML parse error at line 1, column 18
+  |
+1 | ignore_commits = [{ commit = "abc1234", rules = ["subject-lenght"] }]
+  |                  ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+unknown rule `subject-lenght`, expected one of `empty`, `subject-length`, `line-length`, `subject-capitalized`, `subject-not-punctuated`, `imperative`, `wip`, `fixup`, `style`, `allowed-types`, `allowed-scopes`, `merge-commit`, `allowed-author`, `unused-suppression`, `tag-version`, `branch-name`
+help: did you mean `subject-length`?
+
+"#]]);
+}
+
+#[test]
+fn config_check_nested_field() {
+    run_config_check(
+        r#"[merge_rules]
+style = "none"
+ignore_commits = ["abc1234"]
+"#,
+    )
+    .code(78)
+    .stdout_eq(str![])
+    .stderr_eq(str![[r#"
+invalid config in committed.toml: TOML parse error at line 1, column 1
+  |
+1 | [merge_rules]
+  | ^^^^^^^^^^^^^
+`ignore_commits` can't be set in `merge_rules`
+
+"#]]);
+}
+
+#[test]
+fn config_check_nested_tag_field() {
+    run_config_check(
+        r#"[tag_rules]
+tag_subject_version = true
+merge_commit = false
+"#,
+    )
+    .code(78)
+    .stdout_eq(str![])
+    .stderr_eq(str![[r#"
+invalid config in committed.toml: TOML parse error at line 1, column 1
+  |
+1 | [tag_rules]
+  | ^^^^^^^^^^^
+`merge_commit` can't be set in `tag_rules`
+
+"#]]);
+}
+
+#[test]
+fn config_check_nested_ref_field() {
+    run_config_check(
+        r#"[ref_rules."refs/heads/main"]
+ignore_author_re = "dependabot"
+"#,
+    )
+    .code(78)
+    .stdout_eq(str![])
+    .stderr_eq(str![[r#"
+invalid config in committed.toml: TOML parse error at line 1, column 2
+  |
+1 | [ref_rules."refs/heads/main"]
+  |  ^^^^^^^^^
+`ignore_author_re` can't be set in `ref_rules."refs/heads/main"`
+
+"#]]);
+}
+
+#[test]
+fn config_check_valid() {
+    run_config_check(r#"style = "conventional""#)
+        .success()
+        .stdout_eq(str![])
+        .stderr_eq(str![]);
+}
+
+#[test]
+fn config_check_schema_directives() {
+    run_config_check(
+        r#"#:schema ./config.schema.json
+"$schema" = "./config.schema.json"
+style = "conventional"
+"#,
+    )
+    .success()
+    .stdout_eq(str![])
+    .stderr_eq(str![]);
+}
+
+#[track_caller]
+fn run_config_check(config: &str) -> snapbox::cmd::OutputAssert {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_dir = root.path().unwrap();
+    std::fs::write(root_dir.join("committed.toml"), config).unwrap();
+
+    let assert = committed()
+        .arg("--config=committed.toml")
+        .arg("config")
+        .arg("check")
+        .current_dir(root_dir)
+        .assert();
+
+    root.close().unwrap();
+
+    assert
+}
+
 #[test]
 fn in_repo() {
     run_committed_repo("WIP: bad times ahead", "")
@@ -138,12 +799,1156 @@ fn run_committed_repo(message: &str, config: &str) -> snapbox::cmd::OutputAssert
     repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &[])
         .unwrap();
 
-    let assert = snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("committed"))
-        .arg("HEAD")
-        .current_dir(root_dir)
-        .assert();
+    let assert = committed().arg("HEAD").current_dir(root_dir).assert();
 
     root.close().unwrap();
 
     assert
 }
+
+#[test]
+fn repo_config_discovery() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_dir = root.path().unwrap();
+    git2::Repository::init(root_dir).unwrap();
+    std::fs::write(
+        root_dir.join("Cargo.toml"),
+        r#"
+[package]
+name = "greeter"
+
+[package.metadata.committed]
+subject_length = 10
+"#,
+    )
+    .unwrap();
+    // Lower precedence than `Cargo.toml` within the same directory
+    std::fs::write(
+        root_dir.join("pyproject.toml"),
+        r#"
+[tool.committed]
+subject_length = 72
+"#,
+    )
+    .unwrap();
+
+    committed()
+        .arg("-v")
+        .current_dir(root_dir)
+        .stdin("Add greetings for everyone")
+        .assert()
+        .code(1)
+        .stdout_eq(str![[r#"
+-: error Commit subject is too long, 26 exceeds the max length of 10
+
+"#]])
+        .stderr_eq(str![[r#"
+[info] Using repo config [..]Cargo.toml
+
+"#]]);
+
+    root.close().unwrap();
+}
+
+#[test]
+fn init_proposes_config() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_dir = root.path().unwrap();
+
+    let repo = git2::Repository::init(root_dir).unwrap();
+    commit_messages(
+        &repo,
+        &[
+            "feat(parser): Parse greetings",
+            "fix(parser): Handle empty greetings",
+            "docs: Describe greetings",
+        ],
+    );
+
+    committed()
+        .arg("init")
+        .current_dir(root_dir)
+        .assert()
+        .success()
+        .stdout_eq(str![[r##"
+# Generated by `committed init` from the last 3 commits
+subject_length = 50
+subject_capitalized = true
+subject_not_punctuated = true
+imperative_subject = true
+line_length = 72
+style = "conventional"
+allowed_types = [
+    "docs",
+    "feat",
+    "fix",
+]
+allowed_scopes = ["parser"]
+
+"##]])
+        .stderr_eq(str![]);
+    snapbox::assert_data_eq!(
+        std::fs::read_to_string(root_dir.join("committed.toml")).unwrap(),
+        str![[r##"
+# Generated by `committed init` from the last 3 commits
+subject_length = 50
+subject_capitalized = true
+subject_not_punctuated = true
+imperative_subject = true
+line_length = 72
+style = "conventional"
+allowed_types = [
+    "docs",
+    "feat",
+    "fix",
+]
+allowed_scopes = ["parser"]
+
+"##]]
+    );
+
+    root.close().unwrap();
+}
+
+#[test]
+fn init_skips_merges() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_dir = root.path().unwrap();
+
+    let repo = git2::Repository::init(root_dir).unwrap();
+    let ids = commit_messages(
+        &repo,
+        &[
+            "feat(parser): Parse greetings",
+            "fix(parser): Handle empty greetings",
+        ],
+    );
+    let first = repo.find_commit(ids[0]).unwrap();
+    let second = repo.find_commit(ids[1]).unwrap();
+    let sig = repo.signature().unwrap();
+    repo.commit(
+        Some("HEAD"),
+        &sig,
+        &sig,
+        "Merge branch 'greetings'",
+        &second.tree().unwrap(),
+        &[&second, &first],
+    )
+    .unwrap();
+
+    committed()
+        .args(["init", "--max-count=2"])
+        .current_dir(root_dir)
+        .assert()
+        .success()
+        .stdout_eq(str![[r##"
+# Generated by `committed init` from the last 2 commits
+...
+"##]])
+        .stderr_eq(str![]);
+
+    root.close().unwrap();
+}
+
+/// Commit each message on top of `HEAD`, with an empty tree
+fn commit_messages(repo: &git2::Repository, messages: &[&str]) -> Vec<git2::Oid> {
+    let tree = repo
+        .find_tree(repo.index().unwrap().write_tree().unwrap())
+        .unwrap();
+    let sig = repo.signature().unwrap();
+    let mut parents = repo
+        .head()
+        .ok()
+        .map(|head| vec![head.peel_to_commit().unwrap()])
+        .unwrap_or_default();
+    let mut ids = Vec::new();
+    for message in messages {
+        let id = repo
+            .commit(
+                Some("HEAD"),
+                &sig,
+                &sig,
+                message,
+                &tree,
+                &parents.iter().collect::<Vec<_>>(),
+            )
+            .unwrap();
+        parents = vec![repo.find_commit(id).unwrap()];
+        ids.push(id);
+    }
+    ids
+}
+
+#[test]
+fn baseline_grandfathers_findings() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_dir = root.path().unwrap();
+    let repo = git2::Repository::init(root_dir).unwrap();
+    commit_messages(
+        &repo,
+        &[
+            "Initial commit",
+            "Add a subject that goes on and on and on and on and on and on",
+        ],
+    );
+
+    committed()
+        .arg("HEAD~1..HEAD")
+        .arg("--write-baseline=committed-baseline.json")
+        .current_dir(root_dir)
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+[..]: error Commit subject is too long, 61 exceeds the max length of 50
+
+"#]])
+        .stderr_eq(str![[r#"
+Recorded 1 finding to committed-baseline.json
+
+"#]]);
+    snapbox::assert_data_eq!(
+        std::fs::read_to_string(root_dir.join("committed-baseline.json")).unwrap(),
+        str![[r#"
+{
+  "findings": [
+    {
+      "commit": "[..]",
+      "rule": "subject-length"
+    }
+  ]
+}
+
+"#]]
+    );
+
+    commit_messages(&repo, &["added greetings"]);
+    committed()
+        .arg("HEAD~2..HEAD")
+        .arg("--baseline=committed-baseline.json")
+        .current_dir(root_dir)
+        .assert()
+        .code(1)
+        .stdout_eq(str![[r#"
+[..]: error Subject should be in the imperative mood but found `added`
+[..]: error Subject should be capitalized but found `added`
+
+"#]])
+        .stderr_eq(str![[r#"
+Suppressed 1 finding in the baseline
+
+"#]]);
+
+    // Messages outside of commits can't be grandfathered in
+    committed()
+        .arg("--write-baseline=stdin-baseline.json")
+        .current_dir(root_dir)
+        .stdin("bad subject.")
+        .assert()
+        .code(1)
+        .stdout_eq(str![[r#"
+-: error Subject should be capitalized but found `bad`
+-: error Subject should not be punctuated but found `.`
+
+"#]])
+        .stderr_eq(str![[r#"
+Recorded 0 findings to stdin-baseline.json
+
+"#]]);
+    committed()
+        .args(["--tags=v*", "--write-baseline=tags-baseline.json"])
+        .current_dir(root_dir)
+        .assert()
+        .code(2)
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+error: the argument '--tags <PATTERN>' cannot be used with '--write-baseline <WRITE_BASELINE>'
+
+Usage: committed --tags <PATTERN> [COMMITS]...
+
+For more information, try '--help'.
+
+"#]]);
+
+    root.close().unwrap();
+}
+
+#[test]
+fn ignore_commits() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_dir = root.path().unwrap();
+    let repo = git2::Repository::init(root_dir).unwrap();
+    let ids = commit_messages(
+        &repo,
+        &[
+            "Initial commit",
+            "added greetings",
+            "Add a subject that goes on and on and on and on and on and on",
+        ],
+    );
+    std::fs::write(
+        root_dir.join("committed.toml"),
+        format!(
+            r#"
+ignore_commits = [
+    "{}",
+    {{ commit = "{}", rules = ["subject-length"] }},
+    "0000000",
+]
+"#,
+            &ids[1].to_string()[..7],
+            ids[2],
+        ),
+    )
+    .unwrap();
+
+    committed()
+        .arg("HEAD~2..HEAD")
+        .arg("-v")
+        .current_dir(root_dir)
+        .assert()
+        .success()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+[info] Using repo config [..]committed.toml
+[warn] Skipping `ignore_commits` entry `0000000`: object not found - no match for prefix (0000000)
+
+"#]]);
+
+    root.close().unwrap();
+}
+
+#[test]
+fn since_upstream() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_dir = root.path().unwrap();
+    let repo = git2::Repository::init(root_dir).unwrap();
+    let ids = commit_messages(&repo, &["Initial commit"]);
+    let initial = repo.find_commit(ids[0]).unwrap();
+    repo.branch("main", &initial, true).unwrap();
+    let mut feature = repo.branch("feature", &initial, true).unwrap();
+    repo.set_head("refs/heads/feature").unwrap();
+    commit_messages(&repo, &["added greetings"]);
+
+    committed()
+        .arg("--since-upstream")
+        .arg("-v")
+        .current_dir(root_dir)
+        .assert()
+        .code(1)
+        .stdout_eq(str![[r#"
+[..]: error Subject should be in the imperative mood but found `added`
+[..]: error Subject should be capitalized but found `added`
+
+"#]])
+        .stderr_eq(str![[r#"
+...
+[info] Checking since main
+...
+"#]]);
+
+    repo.branch("release", &initial, true).unwrap();
+    commit_messages(&repo, &["Add greetings"]);
+    feature.set_upstream(Some("release")).unwrap();
+    committed()
+        .arg("--since-upstream")
+        .arg("-v")
+        .current_dir(root_dir)
+        .assert()
+        .code(1)
+        .stdout_eq(str![[r#"
+[..]: error Subject should be in the imperative mood but found `added`
+[..]: error Subject should be capitalized but found `added`
+
+"#]])
+        .stderr_eq(str![[r#"
+...
+[info] Checking since upstream release
+...
+"#]]);
+
+    root.close().unwrap();
+}
+
+#[test]
+fn rev_list_arguments() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_dir = root.path().unwrap();
+    let repo = git2::Repository::init(root_dir).unwrap();
+    let ids = commit_messages(&repo, &["Initial commit", "added one"]);
+    let base = repo.find_commit(ids[1]).unwrap();
+    repo.branch("main", &base, true).unwrap();
+    repo.branch("topic", &base, true).unwrap();
+    repo.set_head("refs/heads/topic").unwrap();
+    commit_messages(&repo, &["added topic"]);
+    repo.set_head("refs/heads/main").unwrap();
+    commit_messages(&repo, &["Add two"]);
+
+    committed()
+        .args(["^main", "topic"])
+        .current_dir(root_dir)
+        .assert()
+        .code(1)
+        .stdout_eq(str![[r#"
+[..]: error Subject should be in the imperative mood but found `added`
+[..]: error Subject should be capitalized but found `added`
+
+"#]])
+        .stderr_eq(str![]);
+
+    committed()
+        .args(["--branches=ma*", "--max-count=2"])
+        .current_dir(root_dir)
+        .assert()
+        .code(1)
+        .stdout_eq(str![[r#"
+[..]: error Subject should be in the imperative mood but found `added`
+[..]: error Subject should be capitalized but found `added`
+
+"#]])
+        .stderr_eq(str![]);
+
+    committed()
+        .arg("main^{tree}")
+        .current_dir(root_dir)
+        .assert()
+        .code(64)
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+`main^{tree}` is a tree, not a commit
+
+"#]]);
+
+    root.close().unwrap();
+}
+
+#[test]
+fn merge_traversal() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_dir = root.path().unwrap();
+    std::fs::write(
+        root_dir.join("committed.toml"),
+        r#"
+style = "conventional"
+
+[merge_rules]
+style = "none"
+subject_length = 0
+"#,
+    )
+    .unwrap();
+    std::fs::write(root_dir.join("strict.toml"), r#"style = "conventional""#).unwrap();
+    let repo = git2::Repository::init(root_dir).unwrap();
+    let ids = commit_messages(&repo, &["feat: Add greetings"]);
+    let base = repo.find_commit(ids[0]).unwrap();
+    repo.branch("main", &base, true).unwrap();
+    repo.branch("topic", &base, true).unwrap();
+    repo.set_head("refs/heads/topic").unwrap();
+    let topic = commit_messages(&repo, &["fix: added topic"]);
+    repo.set_head("refs/heads/main").unwrap();
+    let main = commit_messages(&repo, &["docs: Describe greetings"]);
+    let tree = repo.find_commit(main[0]).unwrap().tree().unwrap();
+    let sig = repo.signature().unwrap();
+    repo.commit(
+        Some("HEAD"),
+        &sig,
+        &sig,
+        "Merge pull request #1 from someone/a-rather-long-topic-branch-name",
+        &tree,
+        &[
+            &repo.find_commit(main[0]).unwrap(),
+            &repo.find_commit(topic[0]).unwrap(),
+        ],
+    )
+    .unwrap();
+
+    committed()
+        .arg("HEAD~1..HEAD")
+        .current_dir(root_dir)
+        .assert()
+        .code(1)
+        .stdout_eq(str![[r#"
+[..]: error Subject should be in the imperative mood but found `added`
+[..]: error Subject should be capitalized but found `added`
+
+"#]])
+        .stderr_eq(str![]);
+
+    committed()
+        .args(["HEAD~1..HEAD", "--first-parent"])
+        .current_dir(root_dir)
+        .assert()
+        .success()
+        .stdout_eq(str![])
+        .stderr_eq(str![]);
+
+    committed()
+        .args(["HEAD~1..HEAD", "--merges-only", "--config=strict.toml"])
+        .current_dir(root_dir)
+        .assert()
+        .code(1)
+        .stdout_eq(str![[r#"
+[..]: error Commit is not in Conventional format: Missing type in the commit summary, expected `type: description`
+
+"#]])
+        .stderr_eq(str![]);
+
+    root.close().unwrap();
+}
+
+#[test]
+fn tags() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_dir = root.path().unwrap();
+    std::fs::write(
+        root_dir.join("committed.toml"),
+        r#"
+[tag_rules]
+tag_subject_version = true
+"#,
+    )
+    .unwrap();
+    let repo = git2::Repository::init(root_dir).unwrap();
+    let ids = commit_messages(&repo, &["Initial commit"]);
+    let target = repo.find_object(ids[0], None).unwrap();
+    let sig = repo.signature().unwrap();
+    repo.tag("v1.0.0", &target, &sig, "Release 1.0.0", false)
+        .unwrap();
+    repo.tag("v1.1.0", &target, &sig, "Release the parser", false)
+        .unwrap();
+    repo.tag("other", &target, &sig, "released stuff", false)
+        .unwrap();
+    repo.tag_lightweight("v2.0.0", &target, false).unwrap();
+
+    committed()
+        .args(["--tags", "v*"])
+        .current_dir(root_dir)
+        .assert()
+        .code(1)
+        .stdout_eq(str![[r#"
+v1.1.0: error Subject should mention the version `1.1.0`
+
+"#]])
+        .stderr_eq(str![]);
+    // Tags aren't walked, so history limits don't apply
+    committed()
+        .args(["--all", "--tags", "v*"])
+        .current_dir(root_dir)
+        .assert()
+        .code(2)
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+error: the argument '--all' cannot be used with '--tags <PATTERN>'
+
+Usage: committed --all [COMMITS]...
+
+For more information, try '--help'.
+
+"#]]);
+    committed()
+        .args(["--max-count=1", "hook", "pre-push", "origin", "url"])
+        .current_dir(root_dir)
+        .assert()
+        .code(2)
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+error: subcommands cannot be used with commits, `--commit-file`, `--all`, or other modes
+
+Usage: committed [OPTIONS] [COMMITS]... [COMMAND]
+
+For more information, try '--help'.
+
+"#]]);
+
+    root.close().unwrap();
+}
+
+#[test]
+fn branch_name() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_dir = root.path().unwrap();
+    std::fs::write(
+        root_dir.join("committed.toml"),
+        r#"
+branch_name_re = '^[a-z]+/[A-Z]+-[0-9]+-'
+branch_type_prefix = true
+"#,
+    )
+    .unwrap();
+    let repo = git2::Repository::init(root_dir).unwrap();
+    let ids = commit_messages(&repo, &["Initial commit"]);
+    let head = repo.find_commit(ids[0]).unwrap();
+    repo.branch("feature/short-desc", &head, true).unwrap();
+    repo.set_head("refs/heads/feature/short-desc").unwrap();
+
+    committed()
+        .arg("--branch")
+        .current_dir(root_dir)
+        .assert()
+        .code(1)
+        .stdout_eq(str![[r#"
+feature/short-desc: error Invalid branch name `feature/short-desc`, expected a match for `^[a-z]+/[A-Z]+-[0-9]+-`
+feature/short-desc: error Invalid branch name `feature/short-desc`, expected a prefix of one of ["fix", "feat", "chore", "docs", "style", "refactor", "perf", "test"], like `feat/`
+
+"#]])
+        .stderr_eq(str![]);
+
+    committed()
+        .args(["--branch", "feat/PROJ-123-short-desc"])
+        .current_dir(root_dir)
+        .assert()
+        .success()
+        .stdout_eq(str![])
+        .stderr_eq(str![]);
+
+    root.close().unwrap();
+}
+
+#[test]
+fn pr_event_github() {
+    run_pr_event("github.json")
+        .code(1)
+        .stdout_eq(str![[r#"
+#42: error Subject should be in the imperative mood but found `added`
+#42: error Subject should be capitalized but found `added`
+
+"#]])
+        .stderr_eq(str![]);
+}
+
+#[test]
+fn pr_event_gitlab() {
+    run_pr_event("gitlab.json")
+        .success()
+        .stdout_eq(str![])
+        .stderr_eq(str![]);
+}
+
+#[test]
+fn pr_event_invalid() {
+    run_pr_event("push.json")
+        .code(65)
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+invalid [..]/push.json: expected a GitHub `pull_request` or GitLab `merge_request` event
+
+"#]]);
+}
+
+#[track_caller]
+fn run_pr_event(fixture: &str) -> snapbox::cmd::OutputAssert {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_dir = root.path().unwrap();
+    let config_path = root_dir.join("committed.toml");
+    std::fs::write(&config_path, "").unwrap();
+
+    let assert = committed()
+        .arg("--pr-event")
+        .arg(
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/fixtures/pr-event")
+                .join(fixture),
+        )
+        .arg("--config")
+        .arg(&config_path)
+        .current_dir(root_dir)
+        .assert();
+
+    root.close().unwrap();
+
+    assert
+}
+
+#[test]
+fn squash() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_dir = root.path().unwrap();
+    let repo = git2::Repository::init(root_dir).unwrap();
+    let ids = commit_messages(&repo, &["Initial commit"]);
+    let base = repo.find_commit(ids[0]).unwrap();
+    repo.branch("main", &base, true).unwrap();
+    repo.branch("topic", &base, true).unwrap();
+    repo.set_head("refs/heads/topic").unwrap();
+    commit_messages(
+        &repo,
+        &[
+            "Add greetings",
+            "Fix typo in greetings\n\nCo-authored-by: Alex <alex@example.com>",
+            "Wave goodbye\n\nCo-authored-by: Alex <alex@example.com>",
+        ],
+    );
+
+    committed()
+        .args(["--squash", "main..HEAD", "--print-squash"])
+        .current_dir(root_dir)
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+Add greetings
+
+* Add greetings
+* Fix typo in greetings
+* Wave goodbye
+
+Co-authored-by: Alex <alex@example.com>
+
+"#]])
+        .stderr_eq(str![]);
+
+    committed()
+        .args([
+            "--squash",
+            "main..HEAD",
+            "--squash-title",
+            "added greetings (#12)",
+        ])
+        .current_dir(root_dir)
+        .assert()
+        .code(1)
+        .stdout_eq(str![[r#"
+main..HEAD: error Subject should be in the imperative mood but found `added`
+main..HEAD: error Subject should be capitalized but found `added`
+
+"#]])
+        .stderr_eq(str![]);
+
+    root.close().unwrap();
+}
+
+#[test]
+fn patches() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_dir = root.path().unwrap();
+    let config_path = root_dir.join("committed.toml");
+    std::fs::write(&config_path, r#"allowed_author_re = '@example\.com>$'"#).unwrap();
+
+    committed()
+        .arg("--patch")
+        .arg(std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/patches"))
+        .arg("--config")
+        .arg(&config_path)
+        .arg("-v")
+        .current_dir(root_dir)
+        .assert()
+        .code(1)
+        .stdout_eq(str![[r#"
+[..]/0002-fixed-the-greetings.patch: error Disallowed author `Drive By <drive-by@elsewhere.invalid>` used, please use one matching `@example/.com>$`
+[..]/0002-fixed-the-greetings.patch: error Subject should be in the imperative mood but found `fixed`
+[..]/0002-fixed-the-greetings.patch: error Subject should be capitalized but found `fixed`
+
+"#]])
+        // No warning for the cover letter
+        .stderr_eq(str![[r#"
+[info] Using `--config` [..]committed.toml
+
+"#]]);
+
+    root.close().unwrap();
+}
+
+#[test]
+fn stdin_revs() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_dir = root.path().unwrap();
+    let repo = git2::Repository::init(root_dir).unwrap();
+    let ids = commit_messages(&repo, &["Initial commit", "added one", "Add two"]);
+
+    committed()
+        .arg("--stdin-revs")
+        .current_dir(root_dir)
+        .stdin(format!("{}\n{}\n", ids[2], ids[1]))
+        .assert()
+        .code(1)
+        .stdout_eq(str![[r#"
+[..]: error Subject should be in the imperative mood but found `added`
+[..]: error Subject should be capitalized but found `added`
+
+"#]])
+        .stderr_eq(str![]);
+
+    // Like `git log main..HEAD`
+    committed()
+        .arg("--stdin-revs")
+        .current_dir(root_dir)
+        .stdin(format!(
+            "commit {} (HEAD -> main)
+Author: committed <committed@example.com>
+Date:   Sat Jun 1 12:00:00 2024 +0000
+
+    Add two
+
+commit {}
+Author: committed <committed@example.com>
+Date:   Sat Jun 1 11:00:00 2024 +0000
+
+    added one
+",
+            ids[2], ids[1]
+        ))
+        .assert()
+        .code(1)
+        .stdout_eq(str![[r#"
+[..]: error Subject should be in the imperative mood but found `added`
+[..]: error Subject should be capitalized but found `added`
+
+"#]])
+        .stderr_eq(str![]);
+
+    committed()
+        .arg("--stdin-revs")
+        .current_dir(root_dir)
+        .stdin("added one\n")
+        .assert()
+        .code(64)
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+revspec 'added' not found; class=Reference (4); code=NotFound (-3)
+
+"#]]);
+
+    root.close().unwrap();
+}
+
+#[test]
+fn hook_pre_receive() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_dir = root.path().unwrap();
+    std::fs::write(
+        root_dir.join("committed.toml"),
+        r#"
+subject_capitalized = false
+imperative_subject = false
+
+[ref_rules."refs/heads/main"]
+imperative_subject = true
+"#,
+    )
+    .unwrap();
+    let repo = git2::Repository::init(root_dir).unwrap();
+    // Existing commits, like the punctuated one, aren't checked again
+    let ids = commit_messages(&repo, &["Initial commit.", "added one", "added two"]);
+    repo.set_head("refs/heads/main").unwrap();
+    repo.reference("refs/heads/main", ids[0], true, "reset")
+        .unwrap();
+    repo.find_reference("refs/heads/master")
+        .unwrap()
+        .delete()
+        .unwrap();
+    let zero = git2::Oid::ZERO_SHA1;
+
+    committed()
+        .args(["hook", "pre-receive"])
+        .current_dir(root_dir)
+        .stdin(format!(
+            "{zero} {} refs/heads/topic\n{} {zero} refs/heads/old\n",
+            ids[2], ids[0]
+        ))
+        .assert()
+        .success()
+        .stdout_eq(str![])
+        .stderr_eq(str![]);
+
+    committed()
+        .args(["hook", "pre-receive"])
+        .current_dir(root_dir)
+        .stdin(format!("{} {} refs/heads/main\n", ids[0], ids[2]))
+        .assert()
+        .code(1)
+        .stdout_eq(str![[r#"
+[..]: error Subject should be in the imperative mood but found `added`
+[..]: error Subject should be in the imperative mood but found `added`
+
+"#]])
+        .stderr_eq(str![[r#"
+Rejecting refs/heads/main, see the errors above
+
+"#]]);
+
+    committed()
+        .args(["hook", "pre-receive"])
+        .current_dir(root_dir)
+        .stdin("refs/heads/main\n")
+        .assert()
+        .code(64)
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+invalid `pre-receive` line `refs/heads/main`
+
+"#]]);
+
+    root.close().unwrap();
+}
+
+#[test]
+fn install_hooks() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_dir = root.path().unwrap();
+    let repo = git2::Repository::init(root_dir).unwrap();
+    let hooks_dir = repo.path().join("hooks");
+    let existing = "#!/bin/sh\necho existing\n";
+    std::fs::write(hooks_dir.join("commit-msg"), existing).unwrap();
+
+    // Reinstalling leaves the chained hook alone
+    for _ in 0..2 {
+        committed()
+            .arg("install-hooks")
+            .current_dir(root_dir)
+            .assert()
+            .success()
+            .stdout_eq(str![])
+            .stderr_eq(str![]);
+    }
+    snapbox::assert_data_eq!(
+        std::fs::read_to_string(hooks_dir.join("commit-msg")).unwrap(),
+        str![[r##"
+#!/bin/sh
+# Installed by `committed install-hooks`
+chained="$0.pre-committed"
+if [ -x "$chained" ]; then
+    "$chained" "$@" || exit $?
+fi
+exec committed --fixup --wip --commit-file "$1"
+
+"##]]
+    );
+    assert_eq!(
+        std::fs::read_to_string(hooks_dir.join("commit-msg.pre-committed")).unwrap(),
+        existing
+    );
+
+    committed()
+        .arg("uninstall-hooks")
+        .current_dir(root_dir)
+        .assert()
+        .success()
+        .stdout_eq(str![])
+        .stderr_eq(str![]);
+    assert_eq!(
+        std::fs::read_to_string(hooks_dir.join("commit-msg")).unwrap(),
+        existing
+    );
+    assert!(!hooks_dir.join("commit-msg.pre-committed").exists());
+
+    repo.config()
+        .unwrap()
+        .set_str("core.hooksPath", ".githooks")
+        .unwrap();
+    committed()
+        .args(["install-hooks", "--pre-push", "--prepare-commit-msg"])
+        .current_dir(root_dir)
+        .assert()
+        .success()
+        .stdout_eq(str![])
+        .stderr_eq(str![]);
+    assert!(root_dir.join(".githooks/commit-msg").is_file());
+    assert!(root_dir.join(".githooks/pre-push").is_file());
+    assert!(root_dir.join(".githooks/prepare-commit-msg").is_file());
+
+    root.close().unwrap();
+}
+
+#[test]
+fn hook_pre_push() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_dir = root.path().unwrap();
+    let repo = git2::Repository::init(root_dir).unwrap();
+    // Commits already on the remote, like the punctuated one, aren't checked again
+    let ids = commit_messages(&repo, &["Initial commit.", "Add one", "added two"]);
+    repo.remote("origin", "https://example.com/repo.git")
+        .unwrap();
+    repo.reference("refs/remotes/origin/main", ids[0], true, "fetch")
+        .unwrap();
+    let zero = git2::Oid::ZERO_SHA1;
+
+    committed()
+        .args(["hook", "pre-push", "origin", "https://example.com/repo.git"])
+        .current_dir(root_dir)
+        .stdin(format!(
+            "refs/heads/master {} refs/heads/topic {zero}\n(delete) {zero} refs/heads/old {}\n",
+            ids[2], ids[0]
+        ))
+        .assert()
+        .code(1)
+        .stdout_eq(str![[r#"
+[..]: error Subject should be in the imperative mood but found `added`
+[..]: error Subject should be capitalized but found `added`
+
+"#]])
+        .stderr_eq(str![[r#"
+Rejecting refs/heads/topic, see the errors above
+
+"#]]);
+
+    // Someone else pushed a commit that hasn't been fetched
+    committed()
+        .args([
+            "hook",
+            "pre-push",
+            "https://example.com/repo.git",
+            "https://example.com/repo.git",
+        ])
+        .current_dir(root_dir)
+        .stdin(format!(
+            "refs/heads/master {} refs/heads/main 1111111111111111111111111111111111111111\n",
+            ids[1]
+        ))
+        .assert()
+        .success()
+        .stdout_eq(str![])
+        .stderr_eq(str![]);
+
+    root.close().unwrap();
+}
+
+#[test]
+fn hook_prepare_commit_msg() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_dir = root.path().unwrap();
+    git2::Repository::init(root_dir).unwrap();
+    std::fs::write(
+        root_dir.join("committed.toml"),
+        r#"
+style = "conventional"
+allowed_types = ["feat", "fix", "docs"]
+allowed_scopes = ["parser", "cli"]
+"#,
+    )
+    .unwrap();
+    let message_path = root_dir.join("COMMIT_EDITMSG");
+    let message = "
+# Please enter the commit message for your changes. Lines starting
+# with '#' will be ignored, and an empty message aborts the commit.
+";
+    std::fs::write(&message_path, message).unwrap();
+
+    committed()
+        .args(["hook", "prepare-commit-msg", "COMMIT_EDITMSG", "message"])
+        .current_dir(root_dir)
+        .assert()
+        .success()
+        .stdout_eq(str![])
+        .stderr_eq(str![]);
+    assert_eq!(std::fs::read_to_string(&message_path).unwrap(), message);
+
+    committed()
+        .args(["hook", "prepare-commit-msg", "COMMIT_EDITMSG"])
+        .current_dir(root_dir)
+        .assert()
+        .success()
+        .stdout_eq(str![])
+        .stderr_eq(str![]);
+    snapbox::assert_data_eq!(
+        std::fs::read_to_string(&message_path).unwrap(),
+        str![[r#"
+
+# Format: <type>(<scope>): <description>
+# Types: feat, fix, docs
+# Scopes: parser, cli
+# Subject: at most 50 characters
+# Body: wrap at 72 characters
+#
+# Please enter the commit message for your changes. Lines starting
+# with '#' will be ignored, and an empty message aborts the commit.
+
+"#]]
+    );
+
+    root.close().unwrap();
+}
+
+#[test]
+fn commit() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_dir = root.path().unwrap();
+    std::fs::write(
+        root_dir.join("committed.toml"),
+        r#"
+style = "conventional"
+allowed_types = ["feat", "fix"]
+line_length = 30
+"#,
+    )
+    .unwrap();
+    let repo = git2::Repository::init(root_dir).unwrap();
+    let mut config = repo.config().unwrap();
+    config.set_str("user.name", "Ed Page").unwrap();
+    config.set_str("user.email", "ed@example.com").unwrap();
+
+    committed()
+        .arg("commit")
+        .current_dir(root_dir)
+        .assert()
+        .code(64)
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+No changes staged to commit
+
+"#]]);
+
+    std::fs::create_dir_all(root_dir.join("parser")).unwrap();
+    std::fs::write(root_dir.join("parser/greet.rs"), "// Hello\n").unwrap();
+    let mut index = repo.index().unwrap();
+    index
+        .add_path(std::path::Path::new("parser/greet.rs"))
+        .unwrap();
+    index.write().unwrap();
+
+    committed()
+        .args(["commit", "--dry-run"])
+        .current_dir(root_dir)
+        .stdin("feat\n\n\nParse greetings\nThe parser now understands hello and goodbye\n.\nFixes: #12\n\n")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+feat(parser): Parse greetings
+
+The parser now understands
+hello and goodbye
+
+Fixes: #12
+
+"#]])
+        .stderr_eq(str![[r#"
+Type [feat, fix]: Scope (default `parser`, `-` for none): Breaking change, describing what users need to do (empty for none): Subject (36 characters left): Body, ending with `.` on its own line:
+Trailers, like `Fixes: #12`, ending with an empty line:
+
+"#]]);
+    assert!(repo.head().is_err());
+
+    // A message that fails the checks is printed rather than committed
+    committed()
+        .arg("commit")
+        .current_dir(root_dir)
+        .stdin("fix\n-\n\nparsed greetings\n.\n\n")
+        .assert()
+        .code(1)
+        .stdout_eq(str![[r#"
+-: error Subject should be in the imperative mood but found `parsed`
+-: error Subject should be capitalized but found `parsed`
+fix: parsed greetings
+
+"#]])
+        .stderr_eq(str![[r#"
+Type [feat, fix]: Scope (default `parser`, `-` for none): Breaking change, describing what users need to do (empty for none): Subject (45 characters left): Body, ending with `.` on its own line:
+Trailers, like `Fixes: #12`, ending with an empty line:
+
+"#]]);
+    assert!(repo.head().is_err());
+
+    committed()
+        .arg("commit")
+        .current_dir(root_dir)
+        .stdin("feat\n\n\nParse greetings\n.\n\n")
+        .assert()
+        .success()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+Type [feat, fix]: Scope (default `parser`, `-` for none): Breaking change, describing what users need to do (empty for none): Subject (36 characters left): Body, ending with `.` on its own line:
+Trailers, like `Fixes: #12`, ending with an empty line:
+
+"#]]);
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(head.message().unwrap(), "feat(parser): Parse greetings");
+    assert_eq!(head.author().name().unwrap(), "Ed Page");
+
+    root.close().unwrap();
+}